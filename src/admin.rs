@@ -0,0 +1,188 @@
+use std::{collections::BTreeMap, sync::atomic::Ordering};
+
+use activitypub_federation::config::Data;
+use axum::{
+    body::{Body, HttpBody},
+    handler::Handler,
+    http::{header, HeaderMap, Method},
+    routing::{get, MethodRouter},
+    Json, Router,
+};
+use serde::Serialize;
+
+use crate::{load, panic::PANICS_TOTAL, policy, Blog, Error, PAGE_SIZE};
+
+// Bump whenever a response shape or route of the admin API changes incompatibly. tests/admin.rs
+// keeps the route list of every version and fails when the routes change without a bump.
+pub const SCHEMA_VERSION: u32 = 1;
+
+struct Route<B> {
+    method: Method,
+    path: &'static str,
+    handler: MethodRouter<(), B>,
+}
+
+fn get_route<H, T, B>(path: &'static str, handler: H) -> Route<B>
+where
+    H: Handler<T, (), B>,
+    T: 'static,
+    B: HttpBody + Send + 'static,
+{
+    Route {
+        method: Method::GET,
+        path,
+        handler: get(handler),
+    }
+}
+
+// Every admin route. Both the mounted router and the list served by /admin/meta come from here, so
+// the two can't drift apart.
+fn routes<B>() -> Vec<Route<B>>
+where
+    B: HttpBody + Send + 'static,
+{
+    vec![
+        get_route("/admin/meta", http_get_meta),
+        get_route("/admin/metrics", http_get_metrics),
+        get_route("/admin/load-errors", load::http_get_load_errors),
+        get_route(
+            "/admin/authors/:name/policy",
+            policy::http_get_author_policy,
+        ),
+    ]
+}
+
+pub fn router<B>() -> Router<(), B>
+where
+    B: HttpBody + Send + 'static,
+{
+    routes().into_iter().fold(Router::new(), |router, route| {
+        router.route(route.path, route.handler)
+    })
+}
+
+fn route_list() -> Vec<String> {
+    routes::<Body>()
+        .into_iter()
+        .map(|route| format!("{} {}", route.method, route.path))
+        .collect()
+}
+
+pub fn authorize(headers: &HeaderMap, data: &Data<Blog>) -> Result<(), Error> {
+    let token = data.admin_token.as_deref().ok_or(Error::NotFound)?;
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(Error::Unauthorized)?;
+
+    // Compare in constant time so the token can't be guessed byte by byte.
+    let matches = given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0;
+    if matches {
+        Ok(())
+    } else {
+        Err(Error::Unauthorized)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    version: &'static str,
+    schema_version: u32,
+    features: Vec<&'static str>,
+    limits: Limits,
+    routes: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Limits {
+    page_size: usize,
+    classes: BTreeMap<&'static str, ClassLimits>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClassLimits {
+    max_concurrent: usize,
+    timeout_ms: u128,
+    max_body: usize,
+}
+
+// Optional parts compiled into this build.
+fn features() -> Vec<&'static str> {
+    let mut features = vec![];
+    if cfg!(feature = "testing") {
+        features.push("testing");
+    }
+    if cfg!(feature = "panic-route") {
+        features.push("panic-route");
+    }
+    features
+}
+
+pub async fn http_get_meta(headers: HeaderMap, data: Data<Blog>) -> Result<Json<Meta>, Error> {
+    authorize(&headers, &data)?;
+    Ok(Json(Meta {
+        version: env!("CARGO_PKG_VERSION"),
+        schema_version: SCHEMA_VERSION,
+        features: features(),
+        limits: Limits {
+            page_size: PAGE_SIZE,
+            classes: data
+                .limits
+                .classes()
+                .into_iter()
+                .map(|(class, limit)| {
+                    (
+                        class,
+                        ClassLimits {
+                            max_concurrent: limit.max_concurrent,
+                            timeout_ms: limit.timeout.as_millis(),
+                            max_body: limit.max_body,
+                        },
+                    )
+                })
+                .collect(),
+        },
+        routes: route_list(),
+    }))
+}
+
+pub async fn http_get_metrics(headers: HeaderMap, data: Data<Blog>) -> Result<String, Error> {
+    authorize(&headers, &data)?;
+    let mut metrics = format!(
+        "# TYPE panics_total counter\npanics_total {}\n",
+        PANICS_TOTAL.load(Ordering::Relaxed)
+    );
+    metrics.push_str("# TYPE requests_in_flight gauge\n");
+    for (class, limit) in data.limits.classes() {
+        metrics.push_str(&format!(
+            "requests_in_flight{{class=\"{}\"}} {}\n",
+            class,
+            limit.in_flight()
+        ));
+    }
+    metrics.push_str("# TYPE requests_limit gauge\n");
+    for (class, limit) in data.limits.classes() {
+        metrics.push_str(&format!(
+            "requests_limit{{class=\"{}\"}} {}\n",
+            class, limit.max_concurrent
+        ));
+    }
+    metrics.push_str("# TYPE requests_rejected_total counter\n");
+    for (class, limit) in data.limits.classes() {
+        metrics.push_str(&format!(
+            "requests_rejected_total{{class=\"{}\"}} {}\n",
+            class,
+            limit.rejected()
+        ));
+    }
+    Ok(metrics)
+}
@@ -0,0 +1,103 @@
+use activitypub_federation::config::Data;
+use axum::{
+    http::{header, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{vary_accept, wants_activity_json, Blog, Error};
+
+fn is_alias(data: &Data<Blog>, domain: &str) -> bool {
+    data.domain_aliases
+        .iter()
+        .any(|alias| domain.eq_ignore_ascii_case(alias))
+}
+
+// Accepts `acct:` resources on the canonical domain or any alias.
+pub fn webfinger_name<'a>(resource: &'a str, data: &Data<Blog>) -> Result<&'a str, Error> {
+    let (name, domain) = resource
+        .strip_prefix("acct:")
+        .and_then(|acct| acct.rsplit_once('@'))
+        .ok_or(Error::NotFound)?;
+    if !domain.eq_ignore_ascii_case(data.domain()) && !is_alias(data, domain) {
+        return Err(Error::NotFound);
+    }
+    Ok(name)
+}
+
+// Browsers that land on an alias host are sent to the canonical one. Webfinger and ActivityPub
+// fetches are answered in place; the ids they return are canonical anyway.
+pub async fn redirect<B>(data: Data<Blog>, request: Request<B>, next: Next<B>) -> Response {
+    let on_alias = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .is_some_and(|host| !host.eq_ignore_ascii_case(data.domain()) && is_alias(&data, host));
+    if !on_alias || request.uri().path() == "/.well-known/webfinger" {
+        return next.run(request).await;
+    }
+    // Whether an alias redirects depends on `Accept`.
+    if wants_activity_json(request.headers()) {
+        return vary_accept(next.run(request).await);
+    }
+
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    vary_accept(
+        (
+            StatusCode::MOVED_PERMANENTLY,
+            [(header::LOCATION, format!("{}{}", data.hostname, path))],
+        )
+            .into_response(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    async fn data() -> Data<Blog> {
+        let mut blog = testing::blog();
+        blog.domain_aliases = vec!["old.example".into(), "alias.example".into()];
+        testing::config(blog).await.unwrap().to_request_data()
+    }
+
+    #[tokio::test]
+    async fn accepts_the_canonical_domain_and_every_alias() {
+        let data = data().await;
+        for domain in [testing::DOMAIN, "old.example", "alias.example"] {
+            let resource = format!("acct:alice@{}", domain);
+            assert_eq!(webfinger_name(&resource, &data).unwrap(), "alice");
+        }
+    }
+
+    #[tokio::test]
+    async fn domains_are_case_insensitive() {
+        let data = data().await;
+        for resource in ["acct:alice@OLD.example", "acct:alice@Alias.Example"] {
+            assert_eq!(webfinger_name(resource, &data).unwrap(), "alice");
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_other_domains_and_resources() {
+        let data = data().await;
+        for resource in [
+            "acct:alice@unrelated.example",
+            "acct:alice@old.example.evil",
+            "acct:alice",
+            "alice@old.example",
+            "https://old.example/users/alice",
+        ] {
+            assert!(
+                matches!(webfinger_name(resource, &data), Err(Error::NotFound)),
+                "{}",
+                resource
+            );
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use std::fmt;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use url::Url;
+
+use crate::{
+    keys::Keys,
+    policy::PolicyOverrides,
+    slug::{self, Slug},
+    Author, Post, PostKind,
+};
+
+#[derive(Debug)]
+pub enum ValidationError {
+    Empty { field: &'static str },
+    InvalidName { field: &'static str },
+    TooFewOptions { field: &'static str, count: usize },
+    Duplicate { field: &'static str },
+    Unknown { field: &'static str },
+    EndsBeforePublished { field: &'static str },
+}
+
+impl ValidationError {
+    pub fn field(&self) -> &'static str {
+        match self {
+            ValidationError::Empty { field }
+            | ValidationError::InvalidName { field }
+            | ValidationError::TooFewOptions { field, .. }
+            | ValidationError::Duplicate { field }
+            | ValidationError::Unknown { field }
+            | ValidationError::EndsBeforePublished { field } => field,
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Empty { field } => write!(f, "{} must not be empty", field),
+            ValidationError::InvalidName { field } => write!(
+                f,
+                "{} may only contain letters, digits, '_', '.' and '-'",
+                field
+            ),
+            ValidationError::TooFewOptions { field, count } => {
+                write!(f, "{} needs at least 2 options, got {}", field, count)
+            }
+            ValidationError::Duplicate { field } => write!(f, "{} is already in use", field),
+            ValidationError::Unknown { field } => write!(f, "{} does not exist", field),
+            ValidationError::EndsBeforePublished { field } => {
+                write!(f, "{} must be after the publish date", field)
+            }
+        }
+    }
+}
+
+impl IntoResponse for ValidationError {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "field": self.field(), "error": self.to_string() })),
+        )
+            .into_response()
+    }
+}
+
+fn valid_name(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '-')
+}
+
+pub struct PostBuilder {
+    post: Post,
+}
+
+impl Post {
+    pub fn builder(
+        author: impl Into<String>,
+        published: DateTime<Utc>,
+        title: impl Into<String>,
+    ) -> PostBuilder {
+        let title = title.into();
+        PostBuilder {
+            post: Post {
+                author: author.into(),
+                published,
+                kind: PostKind::Note,
+                slug: Slug::Title(slug::from_title(&title, &published)),
+                status: published.timestamp().to_string(),
+                title,
+                content: String::new(),
+                tags: vec![],
+                pinned: false,
+                license: None,
+                indexable: None,
+            },
+        }
+    }
+}
+
+impl PostBuilder {
+    pub fn article(mut self) -> Self {
+        self.post.kind = PostKind::Article;
+        self
+    }
+
+    pub fn note(mut self) -> Self {
+        self.post.kind = PostKind::Note;
+        self
+    }
+
+    pub fn share(mut self, link: Url) -> Self {
+        self.post.kind = PostKind::Share { link };
+        self
+    }
+
+    pub fn question(mut self, options: Vec<String>, ends: Option<DateTime<Utc>>) -> Self {
+        self.post.kind = PostKind::Question { options, ends };
+        self
+    }
+
+    pub fn slug(mut self, slug: impl Into<String>) -> Self {
+        self.post.slug = Slug::Explicit(slug.into());
+        self
+    }
+
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.post.content = content.into();
+        self
+    }
+
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.post.tags = tags;
+        self
+    }
+
+    pub fn pinned(mut self, pinned: bool) -> Self {
+        self.post.pinned = pinned;
+        self
+    }
+
+    pub fn license(mut self, license: impl Into<String>) -> Self {
+        self.post.license = Some(license.into());
+        self
+    }
+
+    pub fn indexable(mut self, indexable: bool) -> Self {
+        self.post.indexable = Some(indexable);
+        self
+    }
+
+    pub fn build(self) -> Result<Post, ValidationError> {
+        let post = self.post;
+        if post.author.is_empty() {
+            return Err(ValidationError::Empty { field: "author" });
+        }
+        if post.title.trim().is_empty() {
+            return Err(ValidationError::Empty { field: "title" });
+        }
+        if !slug::valid(post.slug.as_str()) {
+            return Err(ValidationError::InvalidName { field: "slug" });
+        }
+        if post.tags.iter().any(|tag| tag.is_empty()) {
+            return Err(ValidationError::Empty { field: "tags" });
+        }
+        if post.tags.iter().any(|tag| !valid_name(tag)) {
+            return Err(ValidationError::InvalidName { field: "tags" });
+        }
+        if let PostKind::Question { options, ends } = &post.kind {
+            if options.len() < 2 {
+                return Err(ValidationError::TooFewOptions {
+                    field: "options",
+                    count: options.len(),
+                });
+            }
+            if options.iter().any(|option| option.trim().is_empty()) {
+                return Err(ValidationError::Empty { field: "options" });
+            }
+            if ends.is_some_and(|ends| ends <= post.published) {
+                return Err(ValidationError::EndsBeforePublished { field: "ends" });
+            }
+        }
+        Ok(post)
+    }
+}
+
+pub struct AuthorBuilder {
+    author: Author,
+}
+
+impl Author {
+    pub fn builder(name: impl Into<String>, display_name: impl Into<String>) -> AuthorBuilder {
+        AuthorBuilder {
+            author: Author {
+                name: name.into(),
+                display_name: display_name.into(),
+                bio: String::new(),
+                policy: PolicyOverrides::default(),
+                followers: vec![],
+                joined: None,
+                keys: None,
+            },
+        }
+    }
+}
+
+impl AuthorBuilder {
+    pub fn bio(mut self, bio: impl Into<String>) -> Self {
+        self.author.bio = bio.into();
+        self
+    }
+
+    pub fn indexable(mut self, indexable: bool) -> Self {
+        self.author.policy.indexable = Some(indexable);
+        self
+    }
+
+    pub fn discoverable(mut self, discoverable: bool) -> Self {
+        self.author.policy.discoverable = Some(discoverable);
+        self
+    }
+
+    pub fn followers(mut self, followers: Vec<Url>) -> Self {
+        self.author.followers = followers;
+        self
+    }
+
+    pub fn joined(mut self, joined: DateTime<Utc>) -> Self {
+        self.author.joined = Some(joined);
+        self
+    }
+
+    pub fn keys(mut self, keys: Keys) -> Self {
+        self.author.keys = Some(keys);
+        self
+    }
+
+    pub fn build(self) -> Result<Author, ValidationError> {
+        let author = self.author;
+        if author.name.is_empty() {
+            return Err(ValidationError::Empty { field: "name" });
+        }
+        if !valid_name(&author.name) {
+            return Err(ValidationError::InvalidName { field: "name" });
+        }
+        if author.display_name.trim().is_empty() {
+            return Err(ValidationError::Empty {
+                field: "display_name",
+            });
+        }
+        Ok(author)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::{testing, Error};
+
+    fn post(title: &str) -> PostBuilder {
+        Post::builder(testing::AUTHOR, testing::epoch(), title)
+    }
+
+    fn poll(options: &[&str], ends: Option<DateTime<Utc>>) -> PostBuilder {
+        post("Poll").question(options.iter().map(|o| o.to_string()).collect(), ends)
+    }
+
+    fn tags(tags: &[&str]) -> PostBuilder {
+        post("Tagged").tags(tags.iter().map(|t| t.to_string()).collect())
+    }
+
+    fn rejected<T>(built: Result<T, ValidationError>) -> (&'static str, String) {
+        match built {
+            Ok(_) => panic!("expected a validation error"),
+            Err(error) => (error.field(), error.to_string()),
+        }
+    }
+
+    #[test]
+    fn posts_name_the_invalid_field() {
+        let cases = [
+            (
+                Post::builder("", testing::epoch(), "Title"),
+                "author",
+                "author must not be empty",
+            ),
+            (post(" \n"), "title", "title must not be empty"),
+            (
+                post("Title").slug("no spaces"),
+                "slug",
+                "slug may only contain letters, digits, '_', '.' and '-'",
+            ),
+            (tags(&["rust", ""]), "tags", "tags must not be empty"),
+            (
+                tags(&["two words"]),
+                "tags",
+                "tags may only contain letters, digits, '_', '.' and '-'",
+            ),
+            (
+                poll(&["Yes"], None),
+                "options",
+                "options needs at least 2 options, got 1",
+            ),
+            (
+                poll(&["Yes", " "], None),
+                "options",
+                "options must not be empty",
+            ),
+            (
+                poll(&["Yes", "No"], Some(testing::epoch())),
+                "ends",
+                "ends must be after the publish date",
+            ),
+        ];
+        for (builder, field, message) in cases {
+            assert_eq!(rejected(builder.build()), (field, message.to_string()));
+        }
+        assert!(
+            poll(&["Yes", "No"], Some(testing::epoch() + Duration::days(1)))
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn authors_name_the_invalid_field() {
+        let cases = [
+            (
+                Author::builder("", "Alice"),
+                "name",
+                "name must not be empty",
+            ),
+            (
+                Author::builder("al ice", "Alice"),
+                "name",
+                "name may only contain letters, digits, '_', '.' and '-'",
+            ),
+            (
+                Author::builder("alice", "  "),
+                "display_name",
+                "display_name must not be empty",
+            ),
+        ];
+        for (builder, field, message) in cases {
+            assert_eq!(rejected(builder.build()), (field, message.to_string()));
+        }
+    }
+
+    // Raised while loading rather than by a builder; see `load` and `slug::disambiguate`.
+    #[test]
+    fn load_time_errors_name_their_field() {
+        let duplicate = ValidationError::Duplicate { field: "slug" };
+        assert_eq!(duplicate.field(), "slug");
+        assert_eq!(duplicate.to_string(), "slug is already in use");
+        let unknown = ValidationError::Unknown { field: "author" };
+        assert_eq!(unknown.field(), "author");
+        assert_eq!(unknown.to_string(), "author does not exist");
+    }
+
+    #[tokio::test]
+    async fn validation_errors_render_as_unprocessable() {
+        let error = Error::Validation(ValidationError::TooFewOptions {
+            field: "options",
+            count: 1,
+        });
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "field": "options",
+                "error": "options needs at least 2 options, got 1",
+            })
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::cmp::Reverse;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+
+use crate::{Error, Post};
+
+// Posts are listed newest first. Slugs are unique, so they break ties between posts published
+// in the same instant and keep every page boundary well defined.
+type Key<'a> = (Reverse<DateTime<Utc>>, &'a str);
+
+fn key(post: &Post) -> Key<'_> {
+    (Reverse(post.published), post.slug())
+}
+
+pub fn sort(posts: &mut [&Post]) {
+    posts.sort_by(|a, b| key(a).cmp(&key(b)));
+}
+
+// An opaque position between two posts, handed out in `after`/`before` page links.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    published: DateTime<Utc>,
+    slug: String,
+}
+
+impl Cursor {
+    pub fn of(post: &Post) -> Self {
+        Cursor {
+            published: post.published,
+            slug: post.slug().to_string(),
+        }
+    }
+
+    fn key(&self) -> Key<'_> {
+        (Reverse(self.published), &self.slug)
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!(
+            "{}.{}:{}",
+            self.published.timestamp(),
+            self.published.timestamp_subsec_nanos(),
+            self.slug
+        ))
+    }
+
+    pub fn decode(token: &str) -> Result<Self, Error> {
+        let decode = || -> Option<Self> {
+            let token = String::from_utf8(URL_SAFE_NO_PAD.decode(token).ok()?).ok()?;
+            let (published, slug) = token.split_once(':')?;
+            let (secs, nanos) = published.split_once('.')?;
+            Some(Cursor {
+                published: DateTime::from_timestamp(secs.parse().ok()?, nanos.parse().ok()?)?,
+                slug: slug.to_string(),
+            })
+        };
+        decode().ok_or(Error::BadRequest("malformed cursor"))
+    }
+}
+
+pub struct Page<'a, 'p> {
+    pub posts: &'a [&'p Post],
+    pub has_prev: bool,
+    pub has_next: bool,
+}
+
+// Up to `size` posts strictly after or before a cursor, or from the start when neither is
+// given. `posts` must be ordered by `sort`.
+pub fn page<'a, 'p>(
+    posts: &'a [&'p Post],
+    after: Option<&Cursor>,
+    before: Option<&Cursor>,
+    size: usize,
+) -> Page<'a, 'p> {
+    let (start, end) = match (after, before) {
+        (_, Some(before)) => {
+            let end = posts.partition_point(|p| key(p) < before.key());
+            (end.saturating_sub(size), end)
+        }
+        (Some(after), None) => {
+            let start = posts.partition_point(|p| key(p) <= after.key());
+            (start, (start + size).min(posts.len()))
+        }
+        (None, None) => (0, size.min(posts.len())),
+    };
+    Page {
+        posts: &posts[start..end],
+        has_prev: start > 0,
+        has_next: end < posts.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::testing;
+
+    // Three seconds with four posts each, so every page boundary falls between same-second posts.
+    fn posts() -> Vec<Post> {
+        (0..12)
+            .map(|i| {
+                testing::post(
+                    testing::AUTHOR,
+                    testing::epoch() + Duration::seconds(i / 4),
+                    &format!("Post {}", i),
+                )
+            })
+            .collect()
+    }
+
+    fn slugs(posts: &[&Post]) -> Vec<String> {
+        posts.iter().map(|p| p.slug().to_string()).collect()
+    }
+
+    #[test]
+    fn walks_same_second_posts_without_skipping_or_repeating() {
+        let posts = posts();
+        let mut sorted = posts.iter().collect::<Vec<_>>();
+        sort(&mut sorted);
+
+        let mut forward = vec![];
+        let mut last = page(&sorted, None, None, 5);
+        loop {
+            forward.extend(slugs(last.posts));
+            if !last.has_next {
+                break;
+            }
+            let after = Cursor::of(last.posts[last.posts.len() - 1]);
+            last = page(&sorted, Some(&after), None, 5);
+        }
+        assert_eq!(forward, slugs(&sorted));
+
+        // Back from the last page, the way a client follows `prev` links.
+        let mut backward = slugs(last.posts);
+        let mut first = last;
+        while first.has_prev {
+            let before = Cursor::of(first.posts[0]);
+            first = page(&sorted, None, Some(&before), 5);
+            backward.splice(0..0, slugs(first.posts));
+        }
+        assert_eq!(backward, slugs(&sorted));
+    }
+
+    #[test]
+    fn cursors_round_trip() {
+        let posts = posts();
+        let cursor = Cursor::of(&posts[5]);
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn rejects_malformed_cursors() {
+        for token in ["garbage", "", &URL_SAFE_NO_PAD.encode("12:slug")] {
+            assert!(matches!(Cursor::decode(token), Err(Error::BadRequest(_))));
+        }
+    }
+}
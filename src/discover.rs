@@ -0,0 +1,244 @@
+use std::{
+    cmp::Reverse,
+    sync::{Arc, Mutex},
+};
+
+use activitypub_federation::config::Data;
+use axum::{
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{format_date, html, Blog, Error, Post, PostKind};
+
+// Indices into `Blog::posts` published on the cached date in earlier years, newest first.
+#[derive(Clone, Default)]
+pub struct OnThisDayCache(Arc<Mutex<Option<Day>>>);
+
+type Day = (NaiveDate, Vec<usize>);
+
+impl Post {
+    // Shares point elsewhere, so they make poor picks for discovery.
+    fn eligible_for_discovery(&self) -> bool {
+        !matches!(self.kind, PostKind::Share { .. })
+    }
+}
+
+impl Blog {
+    // `roll` picks the post, so a fixed value always gives the same one.
+    pub fn random_post(&self, roll: u128) -> Option<&Post> {
+        let posts = self
+            .posts
+            .iter()
+            .filter(|p| p.eligible_for_discovery())
+            .collect::<Vec<_>>();
+        if posts.is_empty() {
+            return None;
+        }
+        Some(posts[(roll % posts.len() as u128) as usize])
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        Utc::now().with_timezone(&self.timezone).date_naive()
+    }
+
+    pub fn on_this_day(&self, today: NaiveDate) -> Result<Vec<&Post>, Error> {
+        let mut cache = self
+            .on_this_day_cache
+            .0
+            .lock()
+            .map_err(|_| anyhow::anyhow!("on this day cache poisoned"))?;
+        let indices = match &*cache {
+            Some((date, indices)) if *date == today => indices.clone(),
+            _ => {
+                let mut indices = self
+                    .posts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| {
+                        let date = p.published.with_timezone(&self.timezone).date_naive();
+                        p.eligible_for_discovery()
+                            && date.month() == today.month()
+                            && date.day() == today.day()
+                            && date.year() < today.year()
+                    })
+                    .map(|(i, _)| i)
+                    .collect::<Vec<_>>();
+                indices.sort_by_key(|&i| Reverse(self.posts[i].published));
+                *cache = Some((today, indices.clone()));
+                indices
+            }
+        };
+        Ok(indices.into_iter().map(|i| &self.posts[i]).collect())
+    }
+}
+
+pub async fn http_get_random(data: Data<Blog>) -> Result<Response, Error> {
+    let post = data
+        .random_post(Uuid::new_v4().as_u128())
+        .ok_or(Error::NotFound)?;
+    Ok((
+        StatusCode::FOUND,
+        [
+            (header::LOCATION, data.post_url(post)),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+    )
+        .into_response())
+}
+
+pub async fn http_get_on_this_day(data: Data<Blog>) -> Result<Html<String>, Error> {
+    let today = data.today();
+    let posts = data.on_this_day(today)?;
+    Ok(Html(html::on_this_day(&data.base_path, today, &posts)))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnThisDayPost {
+    title: String,
+    url: String,
+    published: String,
+    years_ago: i32,
+}
+
+pub async fn http_get_on_this_day_json(data: Data<Blog>) -> Result<Response, Error> {
+    let today = data.today();
+    let posts = data
+        .on_this_day(today)?
+        .into_iter()
+        .map(|post| OnThisDayPost {
+            title: post.title.clone(),
+            url: data.post_url(post),
+            published: format_date(&post.published),
+            years_ago: today.year() - post.published.with_timezone(&data.timezone).year(),
+        })
+        .collect::<Vec<_>>();
+    Ok(([(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")], Json(posts)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, TimeZone};
+
+    use super::*;
+    use crate::testing;
+
+    fn blog(posts: Vec<Post>) -> Blog {
+        Blog::new(
+            testing::HOSTNAME,
+            vec![testing::author(testing::AUTHOR)],
+            posts,
+        )
+        .unwrap()
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32, title: &str) -> Post {
+        testing::post(
+            testing::AUTHOR,
+            Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap(),
+            title,
+        )
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn titles(posts: Vec<&Post>) -> Vec<&str> {
+        posts.into_iter().map(|p| p.title.as_str()).collect()
+    }
+
+    #[test]
+    fn random_post_is_seeded_by_the_roll_and_skips_shares() {
+        let blog = testing::blog();
+        // The fixture posts are a note, an article, a share and a question, in that order.
+        let picks = (0..4)
+            .map(|roll| blog.random_post(roll).unwrap().title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(picks, ["A note", "An article", "A question", "A note"]);
+        assert_eq!(
+            blog.random_post(7).unwrap().title,
+            blog.random_post(7).unwrap().title
+        );
+    }
+
+    #[test]
+    fn random_post_needs_an_eligible_post() {
+        let shares = testing::posts(testing::AUTHOR)
+            .into_iter()
+            .filter(|p| !p.eligible_for_discovery())
+            .collect::<Vec<_>>();
+        assert_eq!(shares.len(), 1);
+        assert!(blog(shares).random_post(0).is_none());
+        assert!(blog(vec![]).random_post(0).is_none());
+    }
+
+    #[test]
+    fn on_this_day_lists_earlier_years_newest_first() {
+        let blog = blog(vec![
+            at(2021, 3, 10, 12, 0, "2021"),
+            at(2023, 3, 10, 12, 0, "2023"),
+            at(2024, 3, 10, 8, 0, "Today"),
+            at(2023, 3, 11, 12, 0, "Next day"),
+        ]);
+        assert_eq!(
+            titles(blog.on_this_day(date(2024, 3, 10)).unwrap()),
+            ["2023", "2021"]
+        );
+        // The cache is per day, so a new date is computed afresh.
+        assert_eq!(
+            titles(blog.on_this_day(date(2024, 3, 11)).unwrap()),
+            ["Next day"]
+        );
+    }
+
+    #[test]
+    fn on_this_day_uses_the_configured_timezone() {
+        let posts = || {
+            vec![
+                at(2023, 3, 10, 23, 30, "Late"),
+                at(2023, 3, 11, 0, 30, "Early"),
+            ]
+        };
+        let utc = blog(posts());
+        assert_eq!(
+            titles(utc.on_this_day(date(2024, 3, 10)).unwrap()),
+            ["Late"]
+        );
+        assert_eq!(
+            titles(utc.on_this_day(date(2024, 3, 11)).unwrap()),
+            ["Early"]
+        );
+
+        let mut ahead = blog(posts());
+        ahead.timezone = FixedOffset::east_opt(3600).unwrap();
+        assert!(ahead.on_this_day(date(2024, 3, 10)).unwrap().is_empty());
+        assert_eq!(
+            titles(ahead.on_this_day(date(2024, 3, 11)).unwrap()),
+            ["Early", "Late"]
+        );
+
+        let mut behind = blog(posts());
+        behind.timezone = FixedOffset::west_opt(3600).unwrap();
+        assert_eq!(
+            titles(behind.on_this_day(date(2024, 3, 10)).unwrap()),
+            ["Early", "Late"]
+        );
+    }
+
+    #[test]
+    fn leap_day_posts_only_come_back_on_leap_days() {
+        let blog = blog(vec![at(2020, 2, 29, 12, 0, "Leap")]);
+        assert!(blog.on_this_day(date(2023, 2, 28)).unwrap().is_empty());
+        assert!(blog.on_this_day(date(2023, 3, 1)).unwrap().is_empty());
+        assert_eq!(
+            titles(blog.on_this_day(date(2024, 2, 29)).unwrap()),
+            ["Leap"]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use activitypub_federation::config::Data;
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{html, Blog, Error, Post};
+
+const WIDTH: u32 = 500;
+const HEIGHT: u32 = 200;
+
+impl Blog {
+    pub fn post_url(&self, post: &Post) -> String {
+        format!("{}/blog/{}", self.base_url(), post.slug())
+    }
+
+    // Resolves a `/blog/:slug` or `/embed/:slug` URL on this blog's origin and base path.
+    pub fn post_by_url(&self, url: &str) -> Result<&Post, Error> {
+        let url = Url::parse(url).map_err(|_| Error::NotFound)?;
+        let hostname = Url::parse(&self.hostname)?;
+        if url.origin() != hostname.origin() {
+            return Err(Error::NotFound);
+        }
+        let path = url
+            .path()
+            .strip_prefix(self.base_path.as_str())
+            .ok_or(Error::NotFound)?;
+        let mut segments = path.strip_prefix('/').ok_or(Error::NotFound)?.split('/');
+        match (segments.next(), segments.next(), segments.next()) {
+            (Some("blog" | "embed"), Some(slug), None) => self.post(slug),
+            _ => Err(Error::NotFound),
+        }
+    }
+}
+
+pub async fn http_get_embed(Path(slug): Path<String>, data: Data<Blog>) -> Result<Response, Error> {
+    let post = data.post(&slug)?;
+    let author = data.author(&post.author)?;
+    Ok((
+        [("content-security-policy", "frame-ancestors *")],
+        Html(html::embed(post, author, &data.post_url(post))),
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+pub struct OEmbedQuery {
+    url: String,
+    format: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct OEmbed {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    version: &'static str,
+    title: String,
+    author_name: String,
+    author_url: String,
+    provider_name: String,
+    provider_url: String,
+    html: String,
+    width: u32,
+    height: u32,
+}
+
+pub async fn http_get_oembed(
+    Query(query): Query<OEmbedQuery>,
+    data: Data<Blog>,
+) -> Result<Response, Error> {
+    if query
+        .format
+        .as_deref()
+        .is_some_and(|format| format != "json")
+    {
+        return Ok(StatusCode::NOT_IMPLEMENTED.into_response());
+    }
+
+    let post = data.post_by_url(&query.url)?;
+    let author = data.author(&post.author)?;
+    Ok(Json(OEmbed {
+        kind: "rich",
+        version: "1.0",
+        title: post.title.clone(),
+        author_name: author.display_name.clone(),
+        author_url: format!("{}/users/{}", data.base_url(), author.name),
+        provider_name: data.domain().to_string(),
+        provider_url: data.base_url(),
+        html: format!(
+            "<iframe src=\"{}/embed/{}\" width=\"{}\" height=\"{}\" frameborder=\"0\" title=\"{}\"></iframe>",
+            data.base_url(),
+            html::escape(post.slug()),
+            WIDTH,
+            HEIGHT,
+            html::escape(&post.title),
+        ),
+        width: WIDTH,
+        height: HEIGHT,
+    })
+    .into_response())
+}
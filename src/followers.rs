@@ -0,0 +1,105 @@
+use url::Url;
+
+use crate::Author;
+
+// Collapses the URL forms a remote server may have used for the same actor over time:
+// http vs https, a `www.` prefix, explicit default ports, fragments and trailing slashes.
+pub fn canonical_actor_id(id: &Url) -> Url {
+    let mut id = id.clone();
+    if id.scheme() == "http" {
+        let _ = id.set_scheme("https");
+    }
+    if let Some(host) = id.host_str().and_then(|h| h.strip_prefix("www.")) {
+        let host = host.to_string();
+        let _ = id.set_host(Some(&host));
+    }
+    if id.port() == Some(443) {
+        let _ = id.set_port(None);
+    }
+    id.set_fragment(None);
+    if id.path().len() > 1 && id.path().ends_with('/') {
+        let path = id.path().trim_end_matches('/').to_string();
+        id.set_path(&path);
+    }
+    id
+}
+
+impl Author {
+    // Pairs of followers that look like the same actor, each as (earlier, later). They are only
+    // reported: merging them needs the actor documents fetched and their `id`s compared, since a
+    // server can serve different actors on http and https or with and without `www.`.
+    pub fn suspected_duplicate_followers(&self) -> Vec<(&Url, &Url)> {
+        let mut seen: Vec<(Url, &Url)> = Vec::with_capacity(self.followers.len());
+        let mut duplicates = vec![];
+        for follower in &self.followers {
+            let canonical = canonical_actor_id(follower);
+            match seen.iter().find(|(id, _)| *id == canonical) {
+                Some((_, first)) => duplicates.push((*first, follower)),
+                None => seen.push((canonical, follower)),
+            }
+        }
+        duplicates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(id: &str) -> String {
+        canonical_actor_id(&Url::parse(id).unwrap()).to_string()
+    }
+
+    #[test]
+    fn collapses_url_variants() {
+        let id = "https://example.com/users/bob";
+        for variant in [
+            "http://example.com/users/bob",
+            "https://www.example.com/users/bob",
+            "http://www.example.com/users/bob",
+            "https://example.com:443/users/bob",
+            "https://example.com/users/bob/",
+            "https://example.com/users/bob#main-key",
+            "https://EXAMPLE.com/users/bob",
+        ] {
+            assert_eq!(canonical(variant), id, "{}", variant);
+        }
+    }
+
+    #[test]
+    fn keeps_distinct_actors_apart() {
+        assert_ne!(
+            canonical("https://example.com/users/bob"),
+            canonical("https://example.com/users/Bob")
+        );
+        assert_ne!(
+            canonical("https://example.com/users/bob"),
+            canonical("https://example.com:8443/users/bob")
+        );
+        assert_ne!(
+            canonical("https://example.com/users/bob"),
+            canonical("https://social.example.com/users/bob")
+        );
+        assert_eq!(canonical("https://example.com/"), "https://example.com/");
+    }
+
+    #[test]
+    fn reports_duplicates_without_dropping_them() {
+        let followers = [
+            "https://example.com/users/bob",
+            "https://example.org/users/carol",
+            "http://www.example.com/users/bob/",
+        ]
+        .map(|id| Url::parse(id).unwrap());
+        let author = Author::builder("alice", "Alice")
+            .followers(followers.to_vec())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            author.suspected_duplicate_followers(),
+            vec![(&followers[0], &followers[2])]
+        );
+        assert_eq!(author.followers.len(), 3);
+    }
+}
@@ -0,0 +1,252 @@
+use sha2::{Digest, Sha256};
+
+use crate::{format_date, Blog, Post, PostKind};
+
+fn normalize(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+fn field(hasher: &mut Sha256, name: &str, value: &str) {
+    hasher.update(name.as_bytes());
+    hasher.update((value.len() as u64).to_le_bytes());
+    hasher.update(value.as_bytes());
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl Post {
+    /// Hash of everything about a post that federates or renders.
+    ///
+    /// Covers the author, publish date, title, slug, content, tags, license, indexable override
+    /// and kind-specific data. Text is normalized to `\n` line endings without trailing
+    /// whitespace, and tags are sorted, so reordering front matter or re-saving a file with
+    /// different line endings keeps the hash.
+    /// `pinned` is left out: it only changes how the profile page is laid out.
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        field(&mut hasher, "author", &self.author);
+        field(&mut hasher, "published", &format_date(&self.published));
+        field(&mut hasher, "title", &normalize(&self.title));
+        field(&mut hasher, "slug", self.slug());
+        field(&mut hasher, "content", &normalize(&self.content));
+
+        let mut tags = self.tags.iter().map(|t| normalize(t)).collect::<Vec<_>>();
+        tags.sort();
+        for tag in &tags {
+            field(&mut hasher, "tag", tag);
+        }
+
+        if let Some(license) = &self.license {
+            field(&mut hasher, "license", license);
+        }
+        if let Some(indexable) = self.indexable {
+            field(&mut hasher, "indexable", &indexable.to_string());
+        }
+
+        match &self.kind {
+            PostKind::Article => field(&mut hasher, "kind", "article"),
+            PostKind::Note => field(&mut hasher, "kind", "note"),
+            PostKind::Share { link } => {
+                field(&mut hasher, "kind", "share");
+                field(&mut hasher, "link", link.as_str());
+            }
+            PostKind::Question { options, ends } => {
+                field(&mut hasher, "kind", "question");
+                for option in options {
+                    field(&mut hasher, "option", &normalize(option));
+                }
+                if let Some(ends) = ends {
+                    field(&mut hasher, "ends", &format_date(ends));
+                }
+            }
+        }
+
+        hex(&hasher.finalize())
+    }
+}
+
+impl Blog {
+    /// Hash over the content hashes of all posts, independent of their order, plus the blog and
+    /// author defaults that posts inherit when federated and the base URL every id is built on.
+    pub fn hash(&self) -> String {
+        let mut hashes = self
+            .posts
+            .iter()
+            .map(Post::content_hash)
+            .collect::<Vec<_>>();
+        hashes.sort();
+
+        let mut hasher = Sha256::new();
+        for hash in &hashes {
+            hasher.update(hash.as_bytes());
+        }
+
+        field(&mut hasher, "base_url", &self.base_url());
+        if let Some(license) = &self.default_license {
+            field(&mut hasher, "license", license);
+        }
+        let mut authors = self.authors.iter().collect::<Vec<_>>();
+        authors.sort_by(|a, b| a.name.cmp(&b.name));
+        for author in authors {
+            field(&mut hasher, "author", &author.name);
+            field(
+                &mut hasher,
+                "indexable",
+                &self.policy(author).indexable.to_string(),
+            );
+        }
+        hex(&hasher.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use url::Url;
+
+    use super::*;
+    use crate::{slug::Slug, testing};
+
+    fn post() -> Post {
+        Post::builder(testing::AUTHOR, testing::epoch(), "Title")
+            .content("First line\nSecond line")
+            .tags(vec!["rust".into(), "fediverse".into()])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn ignores_line_endings_and_trailing_whitespace() {
+        let mut crlf = post();
+        crlf.content = "First line  \r\nSecond line\t\r\n\r\n".into();
+        let mut cr = post();
+        cr.content = "First line\rSecond line \r".into();
+        assert_eq!(crlf.content_hash(), post().content_hash());
+        assert_eq!(cr.content_hash(), post().content_hash());
+    }
+
+    #[test]
+    fn ignores_tag_order_and_pinning() {
+        let mut reordered = post();
+        reordered.tags.reverse();
+        let mut pinned = post();
+        pinned.pinned = true;
+        assert_eq!(reordered.content_hash(), post().content_hash());
+        assert_eq!(pinned.content_hash(), post().content_hash());
+    }
+
+    type Change = (&'static str, fn(&mut Post));
+
+    #[test]
+    fn covers_every_federated_field() {
+        let changes: [Change; 10] = [
+            ("author", |p| p.author = "bob".into()),
+            ("published", |p| p.published += Duration::seconds(1)),
+            ("title", |p| p.title = "Other".into()),
+            ("slug", |p| p.slug = Slug::Explicit("other".into())),
+            ("content", |p| p.content = "Other".into()),
+            ("tags", |p| p.tags.push("other".into())),
+            ("license", |p| p.license = Some("CC0-1.0".into())),
+            ("indexable", |p| p.indexable = Some(false)),
+            ("kind", |p| p.kind = PostKind::Article),
+            ("whitespace inside a line", |p| {
+                p.content = "First  line\nSecond line".into()
+            }),
+        ];
+        for (name, change) in changes {
+            let mut changed = post();
+            change(&mut changed);
+            assert_ne!(changed.content_hash(), post().content_hash(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn covers_kind_specific_fields() {
+        let share = |link: &str| PostKind::Share {
+            link: Url::parse(link).unwrap(),
+        };
+        let question = |options: &[&str], ends: Option<i64>| PostKind::Question {
+            options: options.iter().map(|o| o.to_string()).collect(),
+            ends: ends.map(|days| testing::epoch() + Duration::days(days)),
+        };
+        let pairs = [
+            (share("https://example.com/"), share("https://example.org/")),
+            (
+                question(&["Yes", "No"], None),
+                question(&["Yes", "Maybe"], None),
+            ),
+            (
+                question(&["Yes", "No"], None),
+                question(&["No", "Yes"], None),
+            ),
+            (
+                question(&["Yes", "No"], None),
+                question(&["Yes", "No"], Some(1)),
+            ),
+            (
+                question(&["Yes", "No"], Some(1)),
+                question(&["Yes", "No"], Some(2)),
+            ),
+        ];
+        for (a, b) in pairs {
+            let (mut first, mut second) = (post(), post());
+            first.kind = a;
+            second.kind = b;
+            assert_ne!(first.content_hash(), second.content_hash());
+        }
+    }
+
+    #[test]
+    fn blog_hash_ignores_post_order() {
+        let mut posts = testing::posts(testing::AUTHOR);
+        let forward = Blog::new(
+            testing::HOSTNAME,
+            vec![testing::author(testing::AUTHOR)],
+            posts.clone(),
+        )
+        .unwrap();
+        posts.reverse();
+        let backward = Blog::new(
+            testing::HOSTNAME,
+            vec![testing::author(testing::AUTHOR)],
+            posts,
+        )
+        .unwrap();
+        assert_eq!(forward.hash(), backward.hash());
+    }
+
+    #[test]
+    fn blog_hash_covers_ids_and_inherited_defaults() {
+        let base = testing::blog().hash();
+
+        assert_ne!(testing::blog().with_base_path("/blog").hash(), base);
+        let moved = Blog::new(
+            "https://example.com",
+            vec![testing::author(testing::AUTHOR)],
+            testing::posts(testing::AUTHOR),
+        )
+        .unwrap();
+        assert_ne!(moved.hash(), base);
+
+        let mut licensed = testing::blog();
+        licensed.default_license = Some("CC0-1.0".into());
+        assert_ne!(licensed.hash(), base);
+
+        let mut hidden = testing::blog();
+        hidden.policy.indexable = false;
+        assert_ne!(hidden.hash(), base);
+
+        let mut extra = testing::blog();
+        extra.domain_aliases.push("alias.example".into());
+        assert_eq!(extra.hash(), base);
+    }
+}
@@ -0,0 +1,444 @@
+use chrono::{Datelike, NaiveDate};
+use url::form_urlencoded;
+
+use crate::{format_date, license::License, stats::Stats, Author, Post, PostKind};
+
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+pub fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => {
+                in_tag = false;
+                text.push(' ');
+            }
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+fn layout(title: &str, head: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n\
+         <html lang=\"en\">\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n\
+         {}\
+         </head>\n\
+         <body>\n\
+         <main>\n{}</main>\n\
+         </body>\n\
+         </html>\n",
+        escape(title),
+        head,
+        body
+    )
+}
+
+pub fn not_found() -> String {
+    layout(
+        "Not Found",
+        "",
+        "<h1>Not Found</h1>\n<p>The page you were looking for does not exist.</p>\n",
+    )
+}
+
+fn robots(indexable: bool) -> &'static str {
+    if indexable {
+        ""
+    } else {
+        "<meta name=\"robots\" content=\"noindex\">\n"
+    }
+}
+
+fn post_summary(base: &str, post: &Post, tag_href: &str) -> String {
+    format!(
+        "<article>\n\
+         <h3><a href=\"{}/blog/{}\">{}</a></h3>\n\
+         <p><time datetime=\"{}\">{}</time></p>\n\
+         {}\
+         {}\
+         </article>\n",
+        escape(base),
+        escape(post.slug()),
+        escape(&post.title),
+        format_date(&post.published),
+        post.published.format("%B %-d, %Y"),
+        tag_list(post, tag_href),
+        post_body(post),
+    )
+}
+
+fn post_body(post: &Post) -> String {
+    match &post.kind {
+        PostKind::Article | PostKind::Note => format!("<div>{}</div>\n", post.content),
+        PostKind::Share { link } => format!(
+            "<div>{}</div>\n<p><a href=\"{}\">{}</a></p>\n",
+            post.content,
+            escape(link.as_str()),
+            escape(link.as_str())
+        ),
+        PostKind::Question { options, ends } => {
+            let options = options
+                .iter()
+                .map(|option| format!("<li>{}</li>", escape(option)))
+                .collect::<String>();
+            let ends = ends
+                .map(|ends| {
+                    format!(
+                        "<p>Poll ends <time datetime=\"{}\">{}</time></p>\n",
+                        format_date(&ends),
+                        ends.format("%B %-d, %Y")
+                    )
+                })
+                .unwrap_or_default();
+            format!(
+                "<div>{}</div>\n<ul>{}</ul>\n{}",
+                post.content, options, ends
+            )
+        }
+    }
+}
+
+fn tag_list(post: &Post, href: &str) -> String {
+    if post.tags.is_empty() {
+        return String::new();
+    }
+    let tags = post
+        .tags
+        .iter()
+        .map(|tag| {
+            format!(
+                "<li><a href=\"{}{}\">#{}</a></li>",
+                href,
+                form_urlencoded::byte_serialize(tag.as_bytes()).collect::<String>(),
+                escape(tag)
+            )
+        })
+        .collect::<String>();
+    format!("<ul aria-label=\"Tags\">{}</ul>\n", tags)
+}
+
+pub struct AuthorPage<'a> {
+    pub base: &'a str,
+    pub author: &'a Author,
+    pub handle: &'a str,
+    pub pinned: &'a [&'a Post],
+    pub posts: &'a [&'a Post],
+    pub tag: Option<&'a str>,
+    pub indexable: bool,
+    pub page: usize,
+    pub has_next: bool,
+}
+
+pub fn author(page: &AuthorPage) -> String {
+    let author = page.author;
+    let mut body = format!(
+        "<header>\n\
+         <h1>{}</h1>\n\
+         <p>{}</p>\n\
+         {}\
+         <p>{} followers</p>\n\
+         </header>\n",
+        escape(&author.display_name),
+        escape(page.handle),
+        if author.bio.is_empty() {
+            String::new()
+        } else {
+            format!("<p>{}</p>\n", escape(&author.bio))
+        },
+        author.followers.len(),
+    );
+
+    if let Some(tag) = page.tag {
+        body.push_str(&format!(
+            "<p>Showing posts tagged #{} &middot; <a href=\"?\">Show all posts</a></p>\n",
+            escape(tag)
+        ));
+    }
+
+    if !page.pinned.is_empty() {
+        body.push_str("<section aria-labelledby=\"pinned\">\n<h2 id=\"pinned\">Pinned</h2>\n");
+        for post in page.pinned {
+            body.push_str(&post_summary(page.base, post, "?tag="));
+        }
+        body.push_str("</section>\n");
+    }
+
+    body.push_str("<section aria-label=\"Posts\">\n");
+    let mut year = None;
+    for post in page.posts {
+        if year != Some(post.published.year()) {
+            year = Some(post.published.year());
+            body.push_str(&format!("<h2>{}</h2>\n", post.published.year()));
+        }
+        body.push_str(&post_summary(page.base, post, "?tag="));
+    }
+    if page.pinned.is_empty() && page.posts.is_empty() {
+        body.push_str("<p>No posts yet.</p>\n");
+    }
+    body.push_str("</section>\n");
+
+    if page.page > 1 || page.has_next {
+        let tag = page
+            .tag
+            .map(|tag| {
+                format!(
+                    "&amp;tag={}",
+                    form_urlencoded::byte_serialize(tag.as_bytes()).collect::<String>()
+                )
+            })
+            .unwrap_or_default();
+        body.push_str("<nav aria-label=\"Pagination\">\n");
+        if page.page > 1 {
+            body.push_str(&format!(
+                "<a href=\"?page={}{}\" rel=\"prev\">Newer posts</a>\n",
+                page.page - 1,
+                tag
+            ));
+        }
+        if page.has_next {
+            body.push_str(&format!(
+                "<a href=\"?page={}{}\" rel=\"next\">Older posts</a>\n",
+                page.page + 1,
+                tag
+            ));
+        }
+        body.push_str("</nav>\n");
+    }
+
+    layout(&author.display_name, robots(page.indexable), &body)
+}
+
+pub fn stats(stats: &Stats) -> String {
+    let mut body = String::from("<h1>Stats</h1>\n<dl>\n");
+    body.push_str(&format!(
+        "<dt>Posts</dt><dd>{}</dd>\n<dt>Words written</dt><dd>{}</dd>\n",
+        stats.total_posts, stats.total_words
+    ));
+    if let Some(followers) = stats.followers {
+        body.push_str(&format!("<dt>Followers</dt><dd>{}</dd>\n", followers));
+    }
+    body.push_str(&format!(
+        "<dt>Uptime</dt><dd>{} days, {} hours</dd>\n</dl>\n",
+        stats.uptime_seconds / 86400,
+        stats.uptime_seconds % 86400 / 3600
+    ));
+
+    if !stats.posts_per_year.is_empty() {
+        body.push_str("<h2>Posts per year</h2>\n<table>\n<tr><th scope=\"col\">Year</th><th scope=\"col\">Posts</th></tr>\n");
+        for year in &stats.posts_per_year {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>\n",
+                year.year, year.posts
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+
+    if !stats.top_tags.is_empty() {
+        body.push_str("<h2>Most used tags</h2>\n<ol>\n");
+        for tag in &stats.top_tags {
+            body.push_str(&format!(
+                "<li>#{} ({} posts)</li>\n",
+                escape(&tag.tag),
+                tag.posts
+            ));
+        }
+        body.push_str("</ol>\n");
+    }
+
+    layout("Stats", "", &body)
+}
+
+pub fn tag(base: &str, tag: &str, posts: &[&Post]) -> String {
+    let mut body = format!("<h1>#{}</h1>\n", escape(tag));
+    let tag_href = format!("{}/tags/", base);
+    for post in posts {
+        body.push_str(&post_summary(base, post, &tag_href));
+    }
+    if posts.is_empty() {
+        body.push_str("<p>No posts with this tag.</p>\n");
+    }
+    layout(&format!("#{}", tag), "", &body)
+}
+
+pub fn on_this_day(base: &str, today: NaiveDate, posts: &[&Post]) -> String {
+    let mut body = format!("<h1>On this day: {}</h1>\n", today.format("%B %-d"));
+    let tag_href = format!("{}/tags/", base);
+    let mut year = None;
+    for post in posts {
+        if year != Some(post.published.year()) {
+            year = Some(post.published.year());
+            body.push_str(&format!("<h2>{}</h2>\n", post.published.year()));
+        }
+        body.push_str(&post_summary(base, post, &tag_href));
+    }
+    if posts.is_empty() {
+        body.push_str("<p>Nothing was posted on this day in earlier years.</p>\n");
+    }
+    body.push_str(&format!(
+        "<p><a href=\"{}/random\">Read a random post</a></p>\n",
+        escape(base)
+    ));
+    layout("On this day", "", &body)
+}
+
+fn license_line(license: &License) -> String {
+    match &license.url {
+        Some(url) => format!(
+            "<p>License: <a rel=\"license\" href=\"{}\">{}</a></p>\n",
+            escape(url.as_str()),
+            escape(&license.name)
+        ),
+        None => format!("<p>License: {}</p>\n", escape(&license.name)),
+    }
+}
+
+pub struct PostPage<'a> {
+    pub base: &'a str,
+    pub post: &'a Post,
+    pub author: &'a Author,
+    pub url: &'a str,
+    pub site_name: &'a str,
+    pub handle: &'a str,
+    pub license: Option<&'a License>,
+    pub indexable: bool,
+}
+
+// OpenGraph for link preview cards, plus `fediverse:creator` so Mastodon credits the author.
+fn open_graph(page: &PostPage) -> String {
+    let post = page.post;
+    let mut meta = vec![
+        ("og:type", "article".to_string()),
+        ("og:title", post.title.clone()),
+        ("og:url", page.url.to_string()),
+        ("og:site_name", page.site_name.to_string()),
+        ("article:published_time", format_date(&post.published)),
+    ];
+    let description = excerpt(post, 200);
+    if !description.is_empty() {
+        meta.push(("og:description", description));
+    }
+    meta.extend(post.tags.iter().map(|tag| ("article:tag", tag.clone())));
+
+    let mut head = meta
+        .into_iter()
+        .map(|(property, content)| {
+            format!(
+                "<meta property=\"{}\" content=\"{}\">\n",
+                property,
+                escape(&content)
+            )
+        })
+        .collect::<String>();
+    head.push_str(&format!(
+        "<meta name=\"fediverse:creator\" content=\"{}\">\n",
+        escape(page.handle)
+    ));
+    head
+}
+
+pub fn post(page: &PostPage) -> String {
+    let PostPage { post, author, .. } = *page;
+    let head = format!(
+        "{}{}<link rel=\"alternate\" type=\"application/json+oembed\" href=\"{}/oembed?url={}&amp;format=json\">\n",
+        robots(page.indexable),
+        open_graph(page),
+        escape(page.base),
+        form_urlencoded::byte_serialize(page.url.as_bytes()).collect::<String>(),
+    );
+    let body = format!(
+        "<article>\n\
+         <header>\n\
+         <h1>{}</h1>\n\
+         <p>By <a href=\"{}/users/{}\">{}</a> on <time datetime=\"{}\">{}</time></p>\n\
+         {}\
+         </header>\n\
+         {}\
+         {}\
+         </article>\n",
+        escape(&post.title),
+        escape(page.base),
+        escape(&author.name),
+        escape(&author.display_name),
+        format_date(&post.published),
+        post.published.format("%B %-d, %Y"),
+        tag_list(post, &format!("{}/tags/", page.base)),
+        post_body(post),
+        page.license
+            .map(|license| format!("<footer>\n{}</footer>\n", license_line(license)))
+            .unwrap_or_default(),
+    );
+    layout(&post.title, &head, &body)
+}
+
+pub fn excerpt(post: &Post, max: usize) -> String {
+    let text = strip_tags(&post.content);
+    let mut excerpt = String::new();
+    for word in text.split_whitespace() {
+        if !excerpt.is_empty() && excerpt.len() + word.len() + 1 > max {
+            excerpt.push('…');
+            break;
+        }
+        if !excerpt.is_empty() {
+            excerpt.push(' ');
+        }
+        excerpt.push_str(word);
+    }
+    excerpt
+}
+
+// Standalone document for iframes: inline styles only, and the only link opens the post itself.
+pub fn embed(post: &Post, author: &Author, url: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n\
+         <html lang=\"en\">\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n\
+         <style>\
+         body{{margin:0;font-family:system-ui,sans-serif;color:#222;background:#fff}}\
+         article{{box-sizing:border-box;height:100vh;padding:1em 1.25em;border:1px solid #ddd;border-radius:8px;overflow:hidden}}\
+         h1{{margin:0 0 .25em;font-size:1.2em}}\
+         p{{margin:.25em 0}}\
+         .meta{{color:#666;font-size:.85em}}\
+         a{{color:inherit}}\
+         </style>\n\
+         </head>\n\
+         <body>\n\
+         <article>\n\
+         <h1><a href=\"{url}\" target=\"_blank\" rel=\"noopener\">{title}</a></h1>\n\
+         <p class=\"meta\">{author} &middot; <time datetime=\"{datetime}\">{date}</time></p>\n\
+         <p>{excerpt}</p>\n\
+         </article>\n\
+         </body>\n\
+         </html>\n",
+        title = escape(&post.title),
+        url = escape(url),
+        author = escape(&author.display_name),
+        datetime = format_date(&post.published),
+        date = post.published.format("%B %-d, %Y"),
+        excerpt = escape(&excerpt(post, 280)),
+    )
+}
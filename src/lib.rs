@@ -0,0 +1,1066 @@
+mod admin;
+mod aliases;
+mod builder;
+mod cursor;
+mod discover;
+mod embed;
+mod followers;
+mod hash;
+mod html;
+mod keys;
+mod license;
+mod limits;
+mod load;
+mod outbox;
+mod panic;
+mod policy;
+mod slug;
+mod stats;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+use std::{collections::HashSet, sync::Arc};
+
+use activitypub_federation::{
+    axum::json::FederationJson,
+    config::{Data, FederationConfig, FederationMiddleware},
+    fetch::webfinger::{build_webfinger_response, Webfinger},
+    kinds::{
+        self,
+        activity::{CreateType, QuestionType},
+        actor::PersonType,
+        collection::{OrderedCollectionPageType, OrderedCollectionType},
+        object::{ArticleType, NoteType},
+        public,
+    },
+    protocol::{context::WithContext, public_key::PublicKey},
+};
+use axum::{
+    extract::{Path, Query},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware,
+    response::{Html, IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, FixedOffset, Utc};
+use cursor::Cursor;
+use discover::OnThisDayCache;
+use outbox::OutboxCache;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use slug::Slug;
+use stats::StatsCache;
+use tower_http::{
+    catch_panic::CatchPanicLayer, limit::RequestBodyLimitLayer, set_header::SetResponseHeaderLayer,
+};
+use url::Url;
+
+pub use builder::{AuthorBuilder, PostBuilder, ValidationError};
+pub use keys::{Keys, Multikey};
+pub use limits::{Limits, RouteLimit};
+pub use load::LoadError;
+pub use panic::install_hook as install_panic_hook;
+pub use policy::{Policy, PolicyOverrides};
+pub use stats::StatsConfig;
+
+#[derive(Clone)]
+pub struct Blog {
+    hostname: String,
+    base_path: String,
+    pub admin_token: Option<String>,
+    pub default_license: Option<String>,
+    pub policy: Policy,
+    pub stats: StatsConfig,
+    pub limits: Limits,
+    pub timezone: FixedOffset,
+    pub domain_aliases: Vec<String>,
+    // Shared, because the federation middleware clones the blog into every request.
+    authors: Arc<[Author]>,
+    posts: Arc<[Post]>,
+    started: DateTime<Utc>,
+    stats_cache: StatsCache,
+    outbox_cache: OutboxCache,
+    on_this_day_cache: OnThisDayCache,
+    load_errors: Arc<[LoadError]>,
+}
+
+const PAGE_SIZE: usize = 20;
+
+impl Blog {
+    pub fn new(
+        hostname: impl Into<String>,
+        mut authors: Vec<Author>,
+        mut posts: Vec<Post>,
+    ) -> Result<Self, Error> {
+        // Broken posts are left out rather than keeping the whole blog from starting.
+        let mut load_errors = load::unknown_authors(&authors, &mut posts);
+        load_errors.extend(slug::disambiguate(&mut posts));
+        for error in &load_errors {
+            tracing::warn!(
+                "skipping {:?} ({}): {}",
+                error.title,
+                error.published,
+                error.error
+            );
+        }
+
+        // Status ids are derived from the publish second. Of an author's posts sharing a second,
+        // the earliest keeps the plain id and later ones get a numbered suffix.
+        let mut order = (0..posts.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| posts[i].published);
+        let mut ids = HashSet::new();
+        for i in order {
+            let post = &mut posts[i];
+            let base = post.published.timestamp().to_string();
+            let mut status = base.clone();
+            let mut n = 2;
+            while !ids.insert((post.author.clone(), status.clone())) {
+                status = format!("{}-{}", base, n);
+                n += 1;
+            }
+            if status != base {
+                tracing::warn!(
+                    "{:?} shares its publish second with another post by {}, using status id {}",
+                    post.title,
+                    post.author,
+                    status
+                );
+            }
+            post.status = status;
+        }
+
+        for author in &mut authors {
+            // Authors built without saved keys, like test fixtures, get keys that only live
+            // until restart; the binary loads them with `Keys::load_or_generate`.
+            if author.keys.is_none() {
+                author.keys = Some(Keys::generate()?);
+            }
+            for (first, later) in author.suspected_duplicate_followers() {
+                tracing::warn!(
+                    "{} and {} may be the same follower of {}",
+                    first,
+                    later,
+                    author.name
+                );
+            }
+        }
+
+        Ok(Blog {
+            hostname: hostname.into(),
+            base_path: String::new(),
+            admin_token: None,
+            default_license: None,
+            policy: Policy {
+                indexable: true,
+                discoverable: true,
+            },
+            stats: StatsConfig {
+                enabled: true,
+                hide_followers: false,
+            },
+            limits: Limits::default(),
+            timezone: FixedOffset::east_opt(0).expect("UTC is a valid offset"),
+            domain_aliases: vec![],
+            authors: authors.into(),
+            posts: posts.into(),
+            started: Utc::now(),
+            stats_cache: StatsCache::default(),
+            outbox_cache: OutboxCache::default(),
+            on_this_day_cache: OnThisDayCache::default(),
+            load_errors: load_errors.into(),
+        })
+    }
+
+    // Serves everything except webfinger under `path`, e.g. `/blog` for https://example.com/blog/.
+    pub fn with_base_path(mut self, path: &str) -> Self {
+        let path = path.trim_matches('/');
+        self.base_path = if path.is_empty() {
+            String::new()
+        } else {
+            format!("/{}", path)
+        };
+        self
+    }
+
+    fn base_url(&self) -> String {
+        format!("{}{}", self.hostname, self.base_path)
+    }
+
+    fn warn_unknown_licenses(&self) {
+        for license in self
+            .posts
+            .iter()
+            .filter_map(|p| p.license.as_ref())
+            .chain(&self.default_license)
+        {
+            if !license::is_known(license) {
+                tracing::warn!("unknown license identifier {:?}", license);
+            }
+        }
+    }
+
+    fn author(&self, name: &str) -> Result<&Author, Error> {
+        self.authors
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or(Error::NotFound)
+    }
+
+    fn post(&self, slug: &str) -> Result<&Post, Error> {
+        self.posts
+            .iter()
+            .find(|p| p.slug() == slug)
+            .ok_or(Error::NotFound)
+    }
+
+    fn posts_by<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Post> {
+        self.posts.iter().filter(move |p| p.author == name)
+    }
+}
+
+#[derive(Clone)]
+pub enum PostKind {
+    Article,
+    Note,
+    Share {
+        link: Url,
+    },
+    Question {
+        options: Vec<String>,
+        ends: Option<DateTime<Utc>>,
+    },
+}
+
+#[derive(Clone)]
+pub struct Post {
+    author: String,
+    published: DateTime<Utc>,
+    kind: PostKind,
+    title: String,
+    slug: Slug,
+    // The last segment of the status id, unique per author; see `Blog::new`.
+    status: String,
+    content: String,
+    tags: Vec<String>,
+    pinned: bool,
+    license: Option<String>,
+    indexable: Option<bool>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Internal(anyhow::Error),
+    BadRequest(&'static str),
+    NotFound,
+    Unauthorized,
+    Validation(ValidationError),
+}
+
+impl<T> From<T> for Error
+where
+    T: Into<anyhow::Error>,
+{
+    fn from(t: T) -> Self {
+        Error::Internal(t.into())
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        match self {
+            Error::Internal(err) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("{}", err)).into_response()
+            }
+            Error::BadRequest(reason) => (StatusCode::BAD_REQUEST, reason).into_response(),
+            Error::NotFound => (StatusCode::NOT_FOUND, Html(html::not_found())).into_response(),
+            Error::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                "Unauthorized",
+            )
+                .into_response(),
+            Error::Validation(err) => err.into_response(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Author {
+    name: String,
+    display_name: String,
+    bio: String,
+    policy: PolicyOverrides,
+    followers: Vec<Url>,
+    joined: Option<DateTime<Utc>>,
+    keys: Option<Keys>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectCommon {
+    pub id: Url,
+    pub published: String,
+    pub url: Url,
+    pub to: Vec<Url>,
+    pub cc: Vec<Url>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tag: Vec<Hashtag>,
+    #[serde(
+        rename = "schema:license",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub license: Option<Url>,
+    pub indexable: bool,
+}
+
+kinds::kind!(HashtagType, Hashtag);
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hashtag {
+    #[serde(rename = "type")]
+    pub kind: HashtagType,
+    pub href: Url,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Object {
+    Note(Note),
+    Article(Article),
+    Question(Question),
+}
+
+impl Object {
+    pub fn common(&self) -> &ObjectCommon {
+        match self {
+            Object::Note(note) => &note.common,
+            Object::Article(article) => &article.common,
+            Object::Question(question) => &question.common,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    #[serde(rename = "type")]
+    pub kind: NoteType,
+    #[serde(flatten)]
+    pub common: ObjectCommon,
+    pub content: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Article {
+    #[serde(rename = "type")]
+    pub kind: ArticleType,
+    #[serde(flatten)]
+    pub common: ObjectCommon,
+    pub name: String,
+    pub content: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Question {
+    #[serde(rename = "type")]
+    pub kind: QuestionType,
+    #[serde(flatten)]
+    pub common: ObjectCommon,
+    pub content: String,
+    pub one_of: Vec<QuestionOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionOption {
+    #[serde(rename = "type")]
+    pub kind: NoteType,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Create {
+    #[serde(rename = "type")]
+    pub kind: CreateType,
+    pub id: Url,
+    pub actor: Url,
+    pub published: String,
+    pub to: Vec<Url>,
+    pub cc: Vec<Url>,
+    pub object: Object,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    pub id: Url,
+    #[serde(rename = "type")]
+    pub kind: PersonType,
+    pub preferred_username: String,
+    pub name: String,
+    pub url: Url,
+    pub published: String,
+    pub inbox: Url,
+    pub outbox: Url,
+    pub following: Url,
+    pub followers: Url,
+    pub indexable: bool,
+    pub discoverable: bool,
+    pub public_key: PublicKey,
+    pub assertion_method: Vec<Multikey>,
+}
+
+// Collections only link to their first page; pages are walked with `after`/`before` cursors.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedCollection {
+    #[serde(rename = "type")]
+    pub kind: OrderedCollectionType,
+    pub id: Url,
+    pub total_items: usize,
+    pub first: Url,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedCollectionPage<T> {
+    #[serde(rename = "type")]
+    pub kind: OrderedCollectionPageType,
+    pub id: Url,
+    pub part_of: Url,
+    pub total_items: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<Url>,
+    pub ordered_items: Vec<T>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+struct PageQuery {
+    page: bool,
+    after: Option<String>,
+    before: Option<String>,
+}
+
+impl PageQuery {
+    fn is_page(&self) -> bool {
+        self.page || self.after.is_some() || self.before.is_some()
+    }
+}
+
+fn page_url(collection: &Url, after: Option<&str>, before: Option<&str>) -> Url {
+    let mut url = collection.clone();
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("page", "true");
+        if let Some(after) = after {
+            query.append_pair("after", after);
+        }
+        if let Some(before) = before {
+            query.append_pair("before", before);
+        }
+    }
+    url
+}
+
+fn collection(id: Url, total_items: usize) -> OrderedCollection {
+    OrderedCollection {
+        kind: OrderedCollectionType::OrderedCollection,
+        first: page_url(&id, None, None),
+        id,
+        total_items,
+    }
+}
+
+// `posts` must already be in `cursor::sort` order.
+fn collection_page(
+    data: &Data<Blog>,
+    id: &Url,
+    query: &PageQuery,
+    posts: &[&Post],
+) -> Result<WithContext<OrderedCollectionPage<Create>>, Error> {
+    let after = query.after.as_deref().map(Cursor::decode).transpose()?;
+    let before = query.before.as_deref().map(Cursor::decode).transpose()?;
+    let page = cursor::page(posts, after.as_ref(), before.as_ref(), PAGE_SIZE);
+
+    let items = page
+        .posts
+        .iter()
+        .map(|p| p.into_json(data))
+        .collect::<Result<Vec<_>, _>>()?;
+    let schema = uses_schema(&items);
+    Ok(with_context(
+        OrderedCollectionPage {
+            kind: OrderedCollectionPageType::OrderedCollectionPage,
+            id: page_url(id, query.after.as_deref(), query.before.as_deref()),
+            part_of: id.clone(),
+            total_items: posts.len(),
+            prev: page
+                .has_prev
+                .then(|| page.posts.first())
+                .flatten()
+                .map(|first| page_url(id, None, Some(&Cursor::of(first).encode()))),
+            next: page
+                .has_next
+                .then(|| page.posts.last())
+                .flatten()
+                .map(|last| page_url(id, Some(&Cursor::of(last).encode()), None)),
+            ordered_items: items,
+        },
+        schema,
+    ))
+}
+
+// The schema.org term is only added to the context when something actually uses it.
+fn context(schema: bool) -> Vec<Value> {
+    let mut terms = json!({
+        "toot": "http://joinmastodon.org/ns#",
+        "indexable": "toot:indexable",
+        "discoverable": "toot:discoverable",
+    });
+    if schema {
+        terms["schema"] = json!("http://schema.org/");
+    }
+    vec![
+        json!("https://www.w3.org/ns/activitystreams"),
+        json!("https://w3id.org/security/v1"),
+        terms,
+    ]
+}
+
+fn with_context<T>(inner: T, schema: bool) -> WithContext<T> {
+    WithContext::new(inner, Value::Array(context(schema)))
+}
+
+fn uses_schema(activities: &[Create]) -> bool {
+    activities
+        .iter()
+        .any(|activity| activity.object.common().license.is_some())
+}
+
+fn format_date(date: &DateTime<Utc>) -> String {
+    date.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+// Kind-specific serialization: `Post::into_json` builds the ids, addressing and tags every kind
+// shares into `ObjectCommon`, and the kind turns that into its own ActivityStreams object. The
+// match is exhaustive, so a new kind doesn't compile until it has a representation.
+trait ToObject {
+    fn to_object(&self, post: &Post, common: ObjectCommon) -> Object;
+}
+
+impl ToObject for PostKind {
+    fn to_object(&self, post: &Post, common: ObjectCommon) -> Object {
+        match self {
+            PostKind::Article => Object::Article(Article {
+                kind: ArticleType::Article,
+                common,
+                name: post.title.clone(),
+                content: post.content.clone(),
+            }),
+            PostKind::Note => Object::Note(Note {
+                kind: NoteType::Note,
+                common,
+                content: format!("{}\n---\n\n{}", post.title, post.content),
+            }),
+            PostKind::Share { link } => Object::Note(Note {
+                kind: NoteType::Note,
+                common,
+                content: format!(
+                    "{}\n---\n\n{}\n\n<a href=\"{}\">{}</a>",
+                    post.title,
+                    post.content,
+                    html::escape(link.as_str()),
+                    html::escape(link.as_str())
+                ),
+            }),
+            PostKind::Question { options, ends } => Object::Question(Question {
+                kind: QuestionType::Question,
+                common,
+                content: format!("{}\n---\n\n{}", post.title, post.content),
+                one_of: options
+                    .iter()
+                    .map(|option| QuestionOption {
+                        kind: NoteType::Note,
+                        name: option.clone(),
+                    })
+                    .collect(),
+                end_time: ends.as_ref().map(format_date),
+            }),
+        }
+    }
+}
+
+impl Post {
+    fn indexable(&self, policy: &Policy) -> bool {
+        self.indexable.unwrap_or(policy.indexable)
+    }
+
+    fn slug(&self) -> &str {
+        self.slug.as_str()
+    }
+
+    // Tags may be any alphanumeric text, so they are compared the same Unicode-aware way their
+    // federated `href` is lowercased.
+    fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.to_lowercase();
+        self.tags.iter().any(|t| t.to_lowercase() == tag)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub fn into_json(&self, data: &Data<Blog>) -> Result<Create, Error> {
+        let author = data.author(&self.author)?;
+        let published = format_date(&self.published);
+        let to = vec![Url::parse(&format!(
+            "{}/users/{}/followers",
+            data.base_url(),
+            self.author
+        ))?];
+        let cc = vec![public()];
+
+        Ok(Create {
+            kind: CreateType::Create,
+            id: Url::parse(&format!(
+                "{}/users/{}/statuses/{}/activity",
+                data.base_url(),
+                self.author,
+                self.status
+            ))?,
+            actor: Url::parse(&format!("{}/users/{}", data.base_url(), self.author))?,
+            published: published.clone(),
+            to: to.clone(),
+            cc: cc.clone(),
+            object: self.kind.to_object(
+                self,
+                ObjectCommon {
+                    id: Url::parse(&format!(
+                        "{}/users/{}/statuses/{}",
+                        data.base_url(),
+                        self.author,
+                        self.status
+                    ))?,
+                    published,
+                    url: Url::parse(&format!("{}/blog/{}", data.base_url(), self.slug()))?,
+                    to,
+                    cc,
+                    tag: self
+                        .tags
+                        .iter()
+                        .map(|tag| {
+                            Ok(Hashtag {
+                                kind: HashtagType::Hashtag,
+                                href: Url::parse(&format!(
+                                    "{}/tags/{}",
+                                    data.base_url(),
+                                    tag.to_lowercase()
+                                ))?,
+                                name: format!("#{}", tag),
+                            })
+                        })
+                        .collect::<Result<_, Error>>()?,
+                    license: self.license(data).and_then(|license| license.url),
+                    indexable: self.indexable(&data.policy(author)),
+                },
+            ),
+        })
+    }
+}
+
+impl Author {
+    #[allow(clippy::wrong_self_convention)]
+    pub fn into_json(&self, data: &Data<Blog>) -> Result<Person, Error> {
+        let policy = data.policy(self);
+        let id = Url::parse(&format!("{}/users/{}", data.base_url(), self.name))?;
+        let published = self
+            .joined
+            .or_else(|| data.posts_by(&self.name).map(|p| p.published).min())
+            .unwrap_or(data.started);
+        let keys = self
+            .keys
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{} has no keys", self.name))?;
+        Ok(Person {
+            kind: PersonType::Person,
+            url: id.clone(),
+            published: format_date(&published),
+            public_key: PublicKey {
+                id: format!("{}#main-key", id),
+                owner: id.clone(),
+                public_key_pem: keys.rsa.public_key.clone(),
+            },
+            assertion_method: vec![keys.multikey(&id)?],
+            id,
+            inbox: Url::parse(&format!("{}/users/{}/inbox", data.base_url(), self.name))?,
+            outbox: Url::parse(&format!("{}/users/{}/outbox", data.base_url(), self.name))?,
+            following: Url::parse(&format!(
+                "{}/users/{}/following",
+                data.base_url(),
+                self.name
+            ))?,
+            followers: Url::parse(&format!(
+                "{}/users/{}/followers",
+                data.base_url(),
+                self.name
+            ))?,
+            preferred_username: self.name.clone(),
+            name: self.display_name.clone(),
+            indexable: policy.indexable,
+            discoverable: policy.discoverable,
+        })
+    }
+}
+
+pub fn build_router(config: FederationConfig<Blog>) -> Router {
+    config.warn_unknown_licenses();
+
+    let limit = |limit: &RouteLimit| middleware::from_fn_with_state(limit.clone(), limits::enforce);
+    // Applied outside `limit`, so oversized bodies are refused before taking a permit. Chunked
+    // bodies are counted as they are read.
+    let body_limit = |limit: &RouteLimit| RequestBodyLimitLayer::new(limit.max_body);
+    let limits = config.limits.clone();
+
+    let federation = Router::new()
+        .route("/users/:name", get(http_get_user))
+        .route("/users/:name/outbox", get(outbox::http_get_outbox))
+        .route("/tags/:tag", get(http_get_tag))
+        .layer(limit(&limits.federation))
+        .layer(body_limit(&limits.federation));
+    let pages = Router::new()
+        .route("/blog/:slug", get(http_get_post))
+        .route("/stats", get(stats::http_get_stats))
+        .route("/api/v1/stats", get(stats::http_get_stats_json))
+        .route("/oembed", get(embed::http_get_oembed))
+        .route("/random", get(discover::http_get_random))
+        .route("/on-this-day", get(discover::http_get_on_this_day))
+        .route(
+            "/api/v1/on-this-day",
+            get(discover::http_get_on_this_day_json),
+        )
+        .layer(limit(&limits.pages))
+        .layer(body_limit(&limits.pages));
+    let admin = admin::router()
+        .layer(limit(&limits.admin))
+        .layer(body_limit(&limits.admin));
+
+    // Panics on purpose, so the crate's own tests can cover the panic handler through the real
+    // router. Kept out of `testing`, which embedders enable for fixtures.
+    #[cfg(feature = "panic-route")]
+    let pages = pages.route("/debug/panic", get(panic::http_get_panic));
+
+    let routes = federation
+        .merge(pages)
+        .merge(admin)
+        .layer(SetResponseHeaderLayer::overriding(
+            header::X_FRAME_OPTIONS,
+            HeaderValue::from_static("DENY"),
+        ))
+        // Merged after the X-Frame-Options layer so embeds can be framed by other sites.
+        .merge(
+            Router::new()
+                .route("/embed/:slug", get(embed::http_get_embed))
+                .layer(limit(&limits.pages))
+                .layer(body_limit(&limits.pages)),
+        );
+
+    // Webfinger is looked up at the domain root no matter where the blog is mounted.
+    let router = if config.base_path.is_empty() {
+        routes
+    } else {
+        Router::new().nest(&config.base_path, routes)
+    };
+    router
+        .merge(
+            Router::new()
+                .route("/.well-known/webfinger", get(webfinger))
+                .layer(limit(&limits.federation))
+                .layer(body_limit(&limits.federation)),
+        )
+        .layer(middleware::from_fn(aliases::redirect))
+        .layer(FederationMiddleware::new(config))
+        .layer(CatchPanicLayer::custom(panic::handle_panic))
+}
+
+// Responses chosen by `Accept` must say so, or a shared cache in front of the blog hands HTML to
+// fetchers and JSON to browsers.
+fn vary_accept(mut response: Response) -> Response {
+    let headers = response.headers_mut();
+    let varies = headers
+        .get_all(header::VARY)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|name| name.trim().eq_ignore_ascii_case("accept"));
+    if !varies {
+        headers.append(header::VARY, HeaderValue::from_static("Accept"));
+    }
+    response
+}
+
+fn wants_activity_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .map(|accept| {
+            accept.contains("application/activity+json") || accept.contains("application/ld+json")
+        })
+        .unwrap_or(false)
+}
+
+#[derive(Deserialize)]
+struct ProfileQuery {
+    page: Option<usize>,
+    tag: Option<String>,
+}
+
+async fn http_get_user(
+    Path(name): Path<String>,
+    Query(query): Query<ProfileQuery>,
+    headers: HeaderMap,
+    data: Data<Blog>,
+) -> Result<Response, Error> {
+    let user = data.author(&name)?;
+    if wants_activity_json(&headers) {
+        let person = user.into_json(&data)?;
+        let mut context = context(false);
+        context.splice(2..2, keys::CONTEXT.map(Value::from));
+        return Ok(vary_accept(
+            FederationJson(WithContext::new(person, Value::Array(context))).into_response(),
+        ));
+    }
+
+    let mut posts = data
+        .posts_by(&name)
+        .filter(|p| match &query.tag {
+            Some(tag) => p.has_tag(tag),
+            None => true,
+        })
+        .collect::<Vec<_>>();
+    cursor::sort(&mut posts);
+    let (pinned, posts): (Vec<_>, Vec<_>) = posts.into_iter().partition(|p| p.pinned);
+
+    let page = query.page.unwrap_or(1).max(1);
+    let start = (page - 1).saturating_mul(PAGE_SIZE);
+    let end = start.saturating_add(PAGE_SIZE).min(posts.len());
+    let page_posts = posts.get(start..end).unwrap_or_default();
+    let pinned = if page == 1 { &pinned[..] } else { &[] };
+
+    let handle = format!("@{}@{}", user.name, data.domain());
+    Ok(vary_accept(
+        Html(html::author(&html::AuthorPage {
+            base: &data.base_path,
+            author: user,
+            handle: &handle,
+            pinned,
+            posts: page_posts,
+            tag: query.tag.as_deref(),
+            indexable: data.policy(user).indexable,
+            page,
+            has_next: end < posts.len(),
+        }))
+        .into_response(),
+    ))
+}
+
+async fn http_get_tag(
+    Path(tag): Path<String>,
+    Query(query): Query<PageQuery>,
+    headers: HeaderMap,
+    data: Data<Blog>,
+) -> Result<Response, Error> {
+    let mut posts = data
+        .posts
+        .iter()
+        .filter(|p| p.has_tag(&tag))
+        .collect::<Vec<_>>();
+    cursor::sort(&mut posts);
+
+    if wants_activity_json(&headers) {
+        let id = Url::parse(&format!("{}/tags/{}", data.base_url(), tag.to_lowercase()))?;
+        return Ok(vary_accept(if query.is_page() {
+            FederationJson(collection_page(&data, &id, &query, &posts)?).into_response()
+        } else {
+            FederationJson(with_context(collection(id, posts.len()), false)).into_response()
+        }));
+    }
+
+    Ok(vary_accept(
+        Html(html::tag(&data.base_path, &tag, &posts)).into_response(),
+    ))
+}
+
+async fn http_get_post(Path(slug): Path<String>, data: Data<Blog>) -> Result<Html<String>, Error> {
+    let post = data.post(&slug)?;
+    let author = data.author(&post.author)?;
+    Ok(Html(html::post(&html::PostPage {
+        base: &data.base_path,
+        post,
+        author,
+        url: &data.post_url(post),
+        site_name: data.domain(),
+        handle: &format!("@{}@{}", author.name, data.domain()),
+        license: post.license(&data).as_ref(),
+        indexable: post.indexable(&data.policy(author)),
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct WebfingerQuery {
+    resource: String,
+}
+
+async fn webfinger(
+    Query(query): Query<WebfingerQuery>,
+    data: Data<Blog>,
+) -> Result<Json<Webfinger>, Error> {
+    let name = aliases::webfinger_name(&query.resource, &data)?;
+    let user = data.author(name)?;
+    Ok(Json(build_webfinger_response(
+        format!("acct:{}@{}", user.name, data.domain()),
+        user.into_json(&data)?.id,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    // Every kind goes through the outbox, its own page and the post listings. The match is
+    // exhaustive, so a new kind doesn't compile until it is covered here too.
+    #[tokio::test]
+    async fn every_kind_renders() {
+        let data = testing::config(testing::blog())
+            .await
+            .unwrap()
+            .to_request_data();
+        let author = data.author(testing::AUTHOR).unwrap();
+        let posts = testing::posts(testing::AUTHOR);
+
+        for post in &posts {
+            let create = serde_json::to_value(post.into_json(&data).unwrap()).unwrap();
+            let object = &create["object"];
+            let page = html::post(&html::PostPage {
+                base: &data.base_path,
+                post,
+                author,
+                url: &data.post_url(post),
+                site_name: data.domain(),
+                handle: "@alice@localhost:3000",
+                license: None,
+                indexable: true,
+            });
+            let listing = html::tag(&data.base_path, "all", &[post]);
+
+            assert_eq!(create["type"], "Create");
+            assert!(object["id"]
+                .as_str()
+                .unwrap()
+                .starts_with(create["actor"].as_str().unwrap()));
+            for html in [&page, &listing] {
+                assert!(html.contains(&html::escape(&post.title)), "{}", post.title);
+            }
+
+            match &post.kind {
+                PostKind::Article => {
+                    assert_eq!(object["type"], "Article");
+                    assert_eq!(object["name"], post.title);
+                }
+                PostKind::Note => {
+                    assert_eq!(object["type"], "Note");
+                    assert!(object["content"].as_str().unwrap().contains(&post.content));
+                }
+                PostKind::Share { link } => {
+                    assert_eq!(object["type"], "Note");
+                    let href = format!("href=\"{}\"", link);
+                    assert!(object["content"].as_str().unwrap().contains(&href));
+                    for html in [&page, &listing] {
+                        assert!(html.contains(&href));
+                    }
+                }
+                PostKind::Question { options, ends } => {
+                    assert_eq!(object["type"], "Question");
+                    assert_eq!(object["oneOf"].as_array().unwrap().len(), options.len());
+                    assert_eq!(object["endTime"], format_date(&ends.unwrap()));
+                    for option in options {
+                        assert!(object["oneOf"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .any(|o| o["name"] == *option));
+                        for html in [&page, &listing] {
+                            assert!(html.contains(&format!("<li>{}</li>", option)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn same_second_posts_get_distinct_ids() {
+        let second = testing::epoch();
+        // Loaded out of order, to show the earliest post keeps the plain id.
+        let posts = vec![
+            testing::post(
+                testing::AUTHOR,
+                second + Duration::milliseconds(500),
+                "Later",
+            ),
+            testing::post(testing::AUTHOR, second, "Earliest"),
+            testing::post(
+                testing::AUTHOR,
+                second + Duration::milliseconds(900),
+                "Last",
+            ),
+            testing::post("bob", second, "Bob's"),
+        ];
+        let authors = vec![testing::author(testing::AUTHOR), testing::author("bob")];
+        let blog = Blog::new(testing::HOSTNAME, authors, posts).unwrap();
+        let data = testing::config(blog).await.unwrap().to_request_data();
+
+        let id = |title: &str| {
+            let post = data.posts.iter().find(|p| p.title == title).unwrap();
+            let create = post.into_json(&data).unwrap();
+            let object = serde_json::to_value(&create).unwrap()["object"]["id"].clone();
+            (create.id.to_string(), object.as_str().unwrap().to_string())
+        };
+        let statuses = format!(
+            "{}/users/alice/statuses/{}",
+            testing::HOSTNAME,
+            second.timestamp()
+        );
+        assert_eq!(
+            id("Earliest"),
+            (format!("{}/activity", statuses), statuses.clone())
+        );
+        assert_eq!(
+            id("Later"),
+            (
+                format!("{}-2/activity", statuses),
+                format!("{}-2", statuses)
+            )
+        );
+        assert_eq!(id("Last").1, format!("{}-3", statuses));
+        // Other authors' statuses live under their own actor, so they don't collide.
+        assert_eq!(
+            id("Bob's").1,
+            format!(
+                "{}/users/bob/statuses/{}",
+                testing::HOSTNAME,
+                second.timestamp()
+            )
+        );
+    }
+}
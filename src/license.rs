@@ -0,0 +1,136 @@
+use url::Url;
+
+use crate::{Blog, Post};
+
+const KNOWN: &[(&str, &str, Option<&str>)] = &[
+    ("all-rights-reserved", "All rights reserved", None),
+    (
+        "CC0-1.0",
+        "CC0 1.0",
+        Some("https://creativecommons.org/publicdomain/zero/1.0/"),
+    ),
+    (
+        "CC-BY-4.0",
+        "CC BY 4.0",
+        Some("https://creativecommons.org/licenses/by/4.0/"),
+    ),
+    (
+        "CC-BY-SA-4.0",
+        "CC BY-SA 4.0",
+        Some("https://creativecommons.org/licenses/by-sa/4.0/"),
+    ),
+    (
+        "CC-BY-ND-4.0",
+        "CC BY-ND 4.0",
+        Some("https://creativecommons.org/licenses/by-nd/4.0/"),
+    ),
+    (
+        "CC-BY-NC-4.0",
+        "CC BY-NC 4.0",
+        Some("https://creativecommons.org/licenses/by-nc/4.0/"),
+    ),
+    (
+        "CC-BY-NC-SA-4.0",
+        "CC BY-NC-SA 4.0",
+        Some("https://creativecommons.org/licenses/by-nc-sa/4.0/"),
+    ),
+    (
+        "CC-BY-NC-ND-4.0",
+        "CC BY-NC-ND 4.0",
+        Some("https://creativecommons.org/licenses/by-nc-nd/4.0/"),
+    ),
+    (
+        "MIT",
+        "MIT License",
+        Some("https://spdx.org/licenses/MIT.html"),
+    ),
+    (
+        "Apache-2.0",
+        "Apache License 2.0",
+        Some("https://www.apache.org/licenses/LICENSE-2.0"),
+    ),
+];
+
+pub struct License {
+    pub name: String,
+    pub url: Option<Url>,
+}
+
+// Licenses given as a URL are linked as-is, so only web URLs are let through; anything else,
+// like `javascript:` or `data:`, is shown as plain text and warned about at startup.
+fn web_url(id: &str) -> Option<Url> {
+    Url::parse(id)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+pub fn is_known(id: &str) -> bool {
+    KNOWN
+        .iter()
+        .any(|(known, _, _)| known.eq_ignore_ascii_case(id))
+        || web_url(id).is_some()
+}
+
+pub fn resolve(id: &str) -> License {
+    if let Some((_, name, url)) = KNOWN
+        .iter()
+        .find(|(known, _, _)| known.eq_ignore_ascii_case(id))
+    {
+        return License {
+            name: name.to_string(),
+            url: url.map(|url| Url::parse(url).expect("known license urls are valid")),
+        };
+    }
+    License {
+        name: id.to_string(),
+        url: web_url(id),
+    }
+}
+
+impl Post {
+    pub fn license(&self, blog: &Blog) -> Option<License> {
+        self.license
+            .as_deref()
+            .or(blog.default_license.as_deref())
+            .map(resolve)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_known_identifiers() {
+        let license = resolve("cc-by-4.0");
+        assert_eq!(license.name, "CC BY 4.0");
+        assert_eq!(
+            license.url.unwrap().as_str(),
+            "https://creativecommons.org/licenses/by/4.0/"
+        );
+        assert!(resolve("all-rights-reserved").url.is_none());
+    }
+
+    #[test]
+    fn passes_web_urls_through() {
+        for id in ["https://example.com/license", "http://example.com/license"] {
+            assert!(is_known(id));
+            assert_eq!(resolve(id).url.unwrap().as_str(), id);
+        }
+    }
+
+    #[test]
+    fn never_links_other_schemes() {
+        for id in [
+            "javascript:alert(1)",
+            "data:text/html,<script>alert(1)</script>",
+            "file:///etc/passwd",
+            "mailto:me@example.com",
+        ] {
+            assert!(!is_known(id), "{}", id);
+            let license = resolve(id);
+            assert!(license.url.is_none(), "{}", id);
+            assert_eq!(license.name, id);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{
+    extract::State,
+    http::{header, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tokio::sync::Semaphore;
+
+// Seconds a client is asked to wait after being turned away by a full route class or a handler
+// that ran out of time.
+const RETRY_AFTER: u64 = 5;
+
+#[derive(Clone)]
+pub struct RouteLimit {
+    pub max_concurrent: usize,
+    pub timeout: Duration,
+    pub max_body: usize,
+    permits: Arc<Semaphore>,
+    rejected: Arc<AtomicU64>,
+}
+
+impl RouteLimit {
+    pub fn new(max_concurrent: usize, timeout: Duration, max_body: usize) -> Self {
+        RouteLimit {
+            max_concurrent,
+            timeout,
+            max_body,
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            rejected: Arc::default(),
+        }
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.max_concurrent - self.permits.available_permits()
+    }
+
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+}
+
+// Routes grouped by cost: federation documents are rendered from every post and fetched by
+// crawlers, pages are cheap HTML, and admin is rare but should never be starved by the others.
+#[derive(Clone)]
+pub struct Limits {
+    pub federation: RouteLimit,
+    pub pages: RouteLimit,
+    pub admin: RouteLimit,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            federation: RouteLimit::new(64, Duration::from_secs(10), 64 * 1024),
+            pages: RouteLimit::new(512, Duration::from_secs(30), 64 * 1024),
+            admin: RouteLimit::new(8, Duration::from_secs(10), 64 * 1024),
+        }
+    }
+}
+
+impl Limits {
+    pub fn classes(&self) -> [(&'static str, &RouteLimit); 3] {
+        [
+            ("federation", &self.federation),
+            ("pages", &self.pages),
+            ("admin", &self.admin),
+        ]
+    }
+}
+
+pub async fn enforce<B>(
+    State(limit): State<RouteLimit>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let Ok(_permit) = limit.permits.clone().try_acquire_owned() else {
+        limit.rejected.fetch_add(1, Ordering::Relaxed);
+        return unavailable();
+    };
+
+    // The handler was too slow, not the client, so this is a 503 rather than a 408.
+    match tokio::time::timeout(limit.timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => unavailable(),
+    }
+}
+
+fn unavailable() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, RETRY_AFTER.to_string())],
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, middleware, routing::post, Router};
+    use tower::ServiceExt;
+    use tower_http::limit::RequestBodyLimitLayer;
+
+    use super::*;
+
+    fn router(limit: RouteLimit) -> Router {
+        Router::new()
+            .route(
+                "/slow",
+                post(|| tokio::time::sleep(Duration::from_secs(60))),
+            )
+            .route("/echo", post(|body: String| async move { body }))
+            .layer(middleware::from_fn_with_state(limit.clone(), enforce))
+            .layer(RequestBodyLimitLayer::new(limit.max_body))
+    }
+
+    async fn status(router: Router, uri: &str, body: Body) -> StatusCode {
+        let request = Request::post(uri).body(body).unwrap();
+        router.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn slow_handlers_are_unavailable() {
+        let limit = RouteLimit::new(1, Duration::from_millis(10), 16);
+        assert_eq!(
+            status(router(limit), "/slow", Body::empty()).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn limits_chunked_bodies() {
+        let limit = RouteLimit::new(1, Duration::from_secs(10), 16);
+        assert_eq!(
+            status(router(limit.clone()), "/echo", Body::from("short")).await,
+            StatusCode::OK
+        );
+
+        // A channel body has no Content-Length, like a chunked upload.
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move {
+            for _ in 0..4 {
+                let _ = sender.send_data("0123456789".into()).await;
+            }
+        });
+        assert_eq!(
+            status(router(limit), "/echo", body).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+
+    #[tokio::test]
+    async fn full_classes_are_unavailable() {
+        let limit = RouteLimit::new(0, Duration::from_secs(10), 16);
+        assert_eq!(
+            status(router(limit.clone()), "/echo", Body::empty()).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(limit.rejected(), 1);
+    }
+}
@@ -0,0 +1,139 @@
+use activitypub_federation::config::Data;
+use axum::{http::HeaderMap, Json};
+use serde::Serialize;
+
+use crate::{admin, builder::ValidationError, Author, Blog, Error, Post};
+
+// A post that was left out of the blog instead of stopping it from starting.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadError {
+    pub title: String,
+    pub published: String,
+    pub field: &'static str,
+    pub error: String,
+}
+
+impl LoadError {
+    pub fn new(post: &Post, error: ValidationError) -> Self {
+        LoadError {
+            title: post.title.clone(),
+            published: crate::format_date(&post.published),
+            field: error.field(),
+            error: error.to_string(),
+        }
+    }
+}
+
+pub fn unknown_authors(authors: &[Author], posts: &mut Vec<Post>) -> Vec<LoadError> {
+    let mut errors = vec![];
+    posts.retain(|post| {
+        let known = authors.iter().any(|a| a.name == post.author);
+        if !known {
+            errors.push(LoadError::new(
+                post,
+                ValidationError::Unknown { field: "author" },
+            ));
+        }
+        known
+    });
+    errors
+}
+
+impl Blog {
+    pub fn load_errors(&self) -> &[LoadError] {
+        &self.load_errors
+    }
+
+    // Fail-fast loading, for setups that would rather not start than serve a partial blog.
+    pub fn strict(self) -> Result<Self, Error> {
+        match self.load_errors.first() {
+            Some(error) => {
+                Err(
+                    anyhow::anyhow!("{:?} ({}): {}", error.title, error.published, error.error)
+                        .into(),
+                )
+            }
+            None => Ok(self),
+        }
+    }
+}
+
+pub async fn http_get_load_errors(
+    headers: HeaderMap,
+    data: Data<Blog>,
+) -> Result<Json<Vec<LoadError>>, Error> {
+    admin::authorize(&headers, &data)?;
+    Ok(Json(data.load_errors.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn drops_posts_by_unknown_authors() {
+        let authors = vec![testing::author("alice")];
+        let mut posts = vec![
+            testing::post("alice", testing::epoch(), "Kept"),
+            testing::post("mallory", testing::epoch(), "Dropped"),
+        ];
+        let errors = unknown_authors(&authors, &mut posts);
+
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].title, "Kept");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].title, "Dropped");
+        assert_eq!(errors[0].field, "author");
+        assert_eq!(errors[0].published, "2024-01-01T12:00:00Z");
+    }
+
+    fn broken_blog() -> Blog {
+        let mut posts = testing::posts("alice");
+        posts.push(testing::post("mallory", testing::epoch(), "Orphan"));
+        posts.push(
+            Post::builder("alice", testing::epoch(), "Copy")
+                .slug("a-note")
+                .build()
+                .unwrap(),
+        );
+        posts.push(
+            Post::builder("alice", testing::epoch(), "Copy again")
+                .slug("a-note")
+                .build()
+                .unwrap(),
+        );
+        Blog::new(testing::HOSTNAME, vec![testing::author("alice")], posts).unwrap()
+    }
+
+    #[test]
+    fn blog_starts_without_broken_posts() {
+        let blog = broken_blog();
+        let skipped = blog
+            .load_errors()
+            .iter()
+            .map(|error| (error.title.as_str(), error.field))
+            .collect::<Vec<_>>();
+        assert_eq!(skipped, [("Orphan", "author"), ("Copy again", "slug")]);
+        assert_eq!(blog.posts.len(), testing::posts("alice").len() + 1);
+        assert!(blog.post("a-note").is_ok_and(|post| post.title == "Copy"));
+    }
+
+    #[test]
+    fn strict_refuses_a_partial_blog() {
+        assert!(broken_blog().strict().is_err());
+        assert!(testing::blog().strict().is_ok());
+    }
+
+    #[test]
+    fn bad_dates_are_rejected_when_building() {
+        let built = Post::builder("alice", testing::epoch(), "Poll")
+            .question(
+                vec!["Yes".into(), "No".into()],
+                Some(testing::epoch() - chrono::Duration::days(1)),
+            )
+            .build();
+        assert!(built.is_err_and(|error| error.field() == "ends"));
+    }
+}
@@ -0,0 +1,100 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use activitypub_federation::{config::Data, FEDERATION_CONTENT_TYPE};
+use axum::{
+    body::Bytes,
+    extract::{Path, Query},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use url::Url;
+
+use crate::{collection, collection_page, cursor, with_context, Blog, Error, PageQuery};
+
+// Serialized outbox documents by author, tagged with the ETag of the blog state they were built
+// from. Only the collection and its first page are kept: cursors are minted by clients, so caching
+// the pages behind them would let any crawler grow the map without bound.
+#[derive(Clone, Default)]
+pub struct OutboxCache {
+    version: Arc<OnceLock<String>>,
+    documents: Arc<Mutex<HashMap<Key, (String, Bytes)>>>,
+}
+
+// Author name and whether the document is the first page rather than the collection.
+type Key = (String, bool);
+
+impl OutboxCache {
+    // The blog never changes once it is being served, so its state is hashed on first use only.
+    fn etag(&self, blog: &Blog) -> String {
+        format!("\"{}\"", self.version.get_or_init(|| blog.hash()))
+    }
+}
+
+fn render(data: &Data<Blog>, name: &str, query: &PageQuery) -> Result<Bytes, Error> {
+    let mut posts = data.posts_by(name).collect::<Vec<_>>();
+    cursor::sort(&mut posts);
+    let id = Url::parse(&format!("{}/users/{}/outbox", data.base_url(), name))?;
+    let json = if query.is_page() {
+        serde_json::to_vec(&collection_page(data, &id, query, &posts)?)?
+    } else {
+        serde_json::to_vec(&with_context(collection(id, posts.len()), false))?
+    };
+    Ok(json.into())
+}
+
+pub async fn http_get_outbox(
+    Path(name): Path<String>,
+    Query(query): Query<PageQuery>,
+    headers: HeaderMap,
+    data: Data<Blog>,
+) -> Result<Response, Error> {
+    let _user = data.author(&name)?;
+
+    let etag = data.outbox_cache.etag(&data);
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .is_some_and(|value| value.as_bytes() == etag.as_bytes())
+    {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    if query.after.is_some() || query.before.is_some() {
+        return Ok(outbox_response(etag, render(&data, &name, &query)?));
+    }
+
+    let documents = &data.outbox_cache.documents;
+    let key = (name, query.page);
+    let cached = documents
+        .lock()
+        .map_err(|_| anyhow::anyhow!("outbox cache poisoned"))?
+        .get(&key)
+        .filter(|(version, _)| *version == etag)
+        .map(|(_, body)| body.clone());
+    let body = match cached {
+        Some(body) => body,
+        None => {
+            let body = render(&data, &key.0, &query)?;
+            documents
+                .lock()
+                .map_err(|_| anyhow::anyhow!("outbox cache poisoned"))?
+                .insert(key, (etag.clone(), body.clone()));
+            body
+        }
+    };
+
+    Ok(outbox_response(etag, body))
+}
+
+fn outbox_response(etag: String, body: Bytes) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, FEDERATION_CONTENT_TYPE.to_string()),
+            (header::ETAG, etag),
+        ],
+        body,
+    )
+        .into_response()
+}
@@ -0,0 +1,79 @@
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    cell::RefCell,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use axum::{
+    body::{boxed, BoxBody},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+pub static PANICS_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // The hook runs on the panicking thread right before unwinding, which is the same thread
+    // `CatchPanicLayer` catches it on, so this hands the id over to the 500 response.
+    static LAST_PANIC_ID: RefCell<Option<Uuid>> = const { RefCell::new(None) };
+}
+
+fn payload_str(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "non-string panic payload"
+    }
+}
+
+// `PanicHookInfo` only exists from 1.81; `PanicInfo` is its deprecated alias there.
+#[allow(deprecated)]
+fn hook(info: &std::panic::PanicInfo) {
+    let id = Uuid::new_v4();
+    LAST_PANIC_ID.with(|last| *last.borrow_mut() = Some(id));
+    tracing::error!(
+        request_id = %id,
+        location = info.location().map(ToString::to_string).unwrap_or_default(),
+        backtrace = %Backtrace::force_capture(),
+        "panic: {}",
+        payload_str(info.payload()),
+    );
+}
+
+pub fn install_hook() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        hook(info);
+        // Without a tracing subscriber the message above goes nowhere, so keep stderr output.
+        default(info);
+    }));
+}
+
+pub fn handle_panic(payload: Box<dyn Any + Send + 'static>) -> Response<BoxBody> {
+    PANICS_TOTAL.fetch_add(1, Ordering::Relaxed);
+    // The hook has already logged the panic under its id. Embedders that never installed it still
+    // get the payload logged under the id the client sees.
+    let id = match LAST_PANIC_ID.with(|last| last.borrow_mut().take()) {
+        Some(id) => id,
+        None => {
+            let id = Uuid::new_v4();
+            tracing::error!(request_id = %id, "panic: {}", payload_str(payload.as_ref()));
+            id
+        }
+    };
+    let response = (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("Internal Server Error (request id {})", id),
+    )
+        .into_response();
+    response.map(boxed)
+}
+
+#[cfg(feature = "panic-route")]
+pub async fn http_get_panic() {
+    panic!("panicking on purpose");
+}
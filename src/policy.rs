@@ -0,0 +1,124 @@
+use activitypub_federation::config::Data;
+use axum::{extract::Path, http::HeaderMap, Json};
+use serde::Serialize;
+
+use crate::{admin, Author, Blog, Error};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Policy {
+    pub indexable: bool,
+    pub discoverable: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PolicyOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indexable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discoverable: Option<bool>,
+}
+
+impl PolicyOverrides {
+    pub fn apply(&self, base: &Policy) -> Policy {
+        Policy {
+            indexable: self.indexable.unwrap_or(base.indexable),
+            discoverable: self.discoverable.unwrap_or(base.discoverable),
+        }
+    }
+}
+
+impl Blog {
+    pub fn policy(&self, author: &Author) -> Policy {
+        author.policy.apply(&self.policy)
+    }
+}
+
+#[derive(Serialize)]
+pub struct PolicyReport {
+    effective: Policy,
+    author: PolicyOverrides,
+    global: Policy,
+}
+
+pub async fn http_get_author_policy(
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    data: Data<Blog>,
+) -> Result<Json<PolicyReport>, Error> {
+    admin::authorize(&headers, &data)?;
+    let author = data.author(&name)?;
+    Ok(Json(PolicyReport {
+        effective: data.policy(author),
+        author: author.policy.clone(),
+        global: data.policy.clone(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing, Post};
+
+    const GLOBAL: Policy = Policy {
+        indexable: true,
+        discoverable: true,
+    };
+
+    fn post(indexable: Option<bool>) -> Post {
+        let builder = Post::builder(testing::AUTHOR, testing::epoch(), "Post");
+        match indexable {
+            Some(indexable) => builder.indexable(indexable),
+            None => builder,
+        }
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn global_policy_applies_without_overrides() {
+        let effective = PolicyOverrides::default().apply(&GLOBAL);
+        assert!(effective.indexable && effective.discoverable);
+        assert!(post(None).indexable(&effective));
+    }
+
+    #[test]
+    fn author_overrides_global() {
+        let author = PolicyOverrides {
+            indexable: Some(false),
+            discoverable: None,
+        };
+        let effective = author.apply(&GLOBAL);
+        assert!(!effective.indexable);
+        assert!(effective.discoverable);
+        assert!(!post(None).indexable(&effective));
+    }
+
+    #[test]
+    fn post_overrides_author_and_global() {
+        let hidden = PolicyOverrides {
+            indexable: Some(false),
+            discoverable: Some(false),
+        }
+        .apply(&GLOBAL);
+        assert!(post(Some(true)).indexable(&hidden));
+        assert!(!post(Some(false)).indexable(&GLOBAL));
+    }
+
+    #[test]
+    fn blog_policy_combines_author_and_global() {
+        let mut blog = testing::blog();
+        blog.policy.discoverable = false;
+        let author = crate::Author::builder("bob", "Bob")
+            .discoverable(true)
+            .indexable(false)
+            .build()
+            .unwrap();
+        let effective = blog.policy(&author);
+        assert!(effective.discoverable);
+        assert!(!effective.indexable);
+
+        let default = blog.policy(blog.author(testing::AUTHOR).unwrap());
+        assert!(!default.discoverable);
+        assert!(default.indexable);
+    }
+}
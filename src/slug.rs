@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+
+use crate::{builder::ValidationError, load::LoadError, Post};
+
+#[derive(Clone, Debug)]
+pub enum Slug {
+    Explicit(String),
+    Title(String),
+}
+
+impl Slug {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Slug::Explicit(slug) | Slug::Title(slug) => slug,
+        }
+    }
+}
+
+pub fn valid(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+// Titles with nothing left to slug, like "!!!", fall back to the publish date.
+pub fn from_title(title: &str, published: &DateTime<Utc>) -> String {
+    let slug = title
+        .to_lowercase()
+        .replace(' ', "-")
+        .chars()
+        .filter(|&c| c.is_alphanumeric() || c == '-' || c == '_')
+        .collect::<String>();
+    if slug.is_empty() {
+        published.format("%Y-%m-%d").to_string()
+    } else {
+        slug
+    }
+}
+
+// Explicit slugs are kept as-is and must be unique; of the posts sharing one, the oldest keeps it
+// and the rest are dropped and reported. Title-derived slugs that collide with an explicit slug or
+// an earlier post get the publish date appended, so the oldest post keeps the plain URL and
+// publishing a new post never moves an existing one.
+pub fn disambiguate(posts: &mut Vec<Post>) -> Vec<LoadError> {
+    let mut taken = HashSet::new();
+    let mut explicit = (0..posts.len())
+        .filter(|&i| matches!(posts[i].slug, Slug::Explicit(_)))
+        .collect::<Vec<_>>();
+    explicit.sort_by_key(|&i| posts[i].published);
+    let mut duplicate = vec![false; posts.len()];
+    for i in explicit {
+        duplicate[i] = !taken.insert(posts[i].slug.as_str().to_string());
+    }
+
+    let mut errors = vec![];
+    let mut duplicate = duplicate.into_iter();
+    posts.retain(|post| {
+        let drop = duplicate.next().unwrap_or(false);
+        if drop {
+            errors.push(LoadError::new(
+                post,
+                ValidationError::Duplicate { field: "slug" },
+            ));
+        }
+        !drop
+    });
+
+    let mut order = (0..posts.len())
+        .filter(|&i| matches!(posts[i].slug, Slug::Title(_)))
+        .collect::<Vec<_>>();
+    order.sort_by_key(|&i| posts[i].published);
+
+    for i in order {
+        let post = &mut posts[i];
+        let base = post.slug.as_str().to_string();
+        let mut slug = base.clone();
+        if taken.contains(&slug) {
+            slug = format!("{}-{}", base, post.published.format("%Y-%m-%d"));
+            let dated = slug.clone();
+            let mut n = 2;
+            while taken.contains(&slug) {
+                slug = format!("{}-{}", dated, n);
+                n += 1;
+            }
+            tracing::warn!(
+                "slug {:?} of {:?} is already taken, using {:?}",
+                base,
+                post.title,
+                slug
+            );
+        }
+        taken.insert(slug.clone());
+        post.slug = Slug::Title(slug);
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::testing;
+
+    fn explicit(title: &str, slug: &str, days: i64) -> Post {
+        Post::builder(
+            testing::AUTHOR,
+            testing::epoch() + Duration::days(days),
+            title,
+        )
+        .slug(slug)
+        .build()
+        .unwrap()
+    }
+
+    fn titled(title: &str, published: DateTime<Utc>) -> Post {
+        testing::post(testing::AUTHOR, published, title)
+    }
+
+    fn slugs(posts: &[Post]) -> Vec<&str> {
+        posts.iter().map(Post::slug).collect()
+    }
+
+    #[test]
+    fn drops_later_duplicate_explicit_slugs() {
+        let mut posts = vec![
+            explicit("First", "hello", 0),
+            explicit("Second", "hello", 1),
+            explicit("Third", "other", 2),
+        ];
+        let errors = disambiguate(&mut posts);
+
+        assert_eq!(slugs(&posts), ["hello", "other"]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].title, "Second");
+        assert_eq!(errors[0].field, "slug");
+    }
+
+    #[test]
+    fn oldest_explicit_slug_wins_whatever_the_load_order() {
+        let mut posts = vec![
+            explicit("Newer", "hello", 3),
+            explicit("Other", "other", 2),
+            explicit("Oldest", "hello", 0),
+            explicit("Middle", "hello", 1),
+        ];
+        let errors = disambiguate(&mut posts);
+
+        assert_eq!(slugs(&posts), ["other", "hello"]);
+        assert_eq!(posts[1].title, "Oldest");
+        let dropped = errors
+            .iter()
+            .map(|error| error.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(dropped, ["Newer", "Middle"]);
+    }
+
+    #[test]
+    fn same_titles_across_years_get_dated_slugs() {
+        let year = |year| Utc.with_ymd_and_hms(year, 12, 31, 9, 0, 0).unwrap();
+        // Loaded newest first, to show the order of publishing decides, not the order of loading.
+        let mut posts = vec![
+            titled("Year in review", year(2025)),
+            titled("Year in review", year(2023)),
+            titled("Year in review", year(2024)),
+        ];
+        assert!(disambiguate(&mut posts).is_empty());
+        assert_eq!(
+            slugs(&posts),
+            [
+                "year-in-review-2025-12-31",
+                "year-in-review",
+                "year-in-review-2024-12-31"
+            ]
+        );
+    }
+
+    #[test]
+    fn explicit_slugs_win_over_title_slugs() {
+        // The explicit slug is newer, but the title-derived one still moves out of its way.
+        let mut posts = vec![
+            titled("Hello", testing::epoch()),
+            explicit("Greetings", "hello", 1),
+        ];
+        assert!(disambiguate(&mut posts).is_empty());
+        assert_eq!(slugs(&posts), ["hello-2024-01-01", "hello"]);
+    }
+
+    #[test]
+    fn same_day_collisions_are_numbered() {
+        let mut posts = vec![
+            titled("Hello", testing::epoch()),
+            titled("Hello", testing::epoch() + Duration::hours(1)),
+            titled("Hello", testing::epoch() + Duration::hours(2)),
+        ];
+        disambiguate(&mut posts);
+        assert_eq!(
+            slugs(&posts),
+            ["hello", "hello-2024-01-01", "hello-2024-01-01-2"]
+        );
+    }
+
+    #[test]
+    fn punctuation_titles_fall_back_to_the_date() {
+        assert_eq!(from_title("!!!", &testing::epoch()), "2024-01-01");
+        assert_eq!(titled("!!!", testing::epoch()).slug(), "2024-01-01");
+        assert_eq!(
+            from_title("Hello, World!", &testing::epoch()),
+            "hello-world"
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use activitypub_federation::config::Data;
+use axum::{
+    http::header,
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use chrono::{Datelike, Utc};
+use serde::Serialize;
+
+use crate::{html, Blog, Error};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const TOP_TAGS: usize = 10;
+
+#[derive(Clone)]
+pub struct StatsConfig {
+    pub enabled: bool,
+    pub hide_followers: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct StatsCache(Arc<Mutex<Option<(Instant, Stats)>>>);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    pub total_posts: usize,
+    pub posts_per_year: Vec<YearCount>,
+    pub total_words: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub followers: Option<usize>,
+    pub top_tags: Vec<TagCount>,
+    pub uptime_seconds: i64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct YearCount {
+    pub year: i32,
+    pub posts: usize,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub posts: usize,
+}
+
+fn count_words(html: &str) -> usize {
+    html::strip_tags(html).split_whitespace().count()
+}
+
+fn compute(blog: &Blog) -> Stats {
+    let mut years = BTreeMap::new();
+    let mut tags = HashMap::new();
+    for post in blog.posts.iter() {
+        *years.entry(post.published.year()).or_insert(0) += 1;
+        for tag in &post.tags {
+            *tags.entry(tag.to_lowercase()).or_insert(0) += 1;
+        }
+    }
+
+    let mut top_tags = tags
+        .into_iter()
+        .map(|(tag, posts)| TagCount { tag, posts })
+        .collect::<Vec<_>>();
+    top_tags.sort_by(|a, b| b.posts.cmp(&a.posts).then_with(|| a.tag.cmp(&b.tag)));
+    top_tags.truncate(TOP_TAGS);
+
+    Stats {
+        total_posts: blog.posts.len(),
+        posts_per_year: years
+            .into_iter()
+            .map(|(year, posts)| YearCount { year, posts })
+            .collect(),
+        total_words: blog
+            .posts
+            .iter()
+            .map(|p| count_words(&p.title) + count_words(&p.content))
+            .sum(),
+        followers: (!blog.stats.hide_followers)
+            .then(|| blog.authors.iter().map(|a| a.followers.len()).sum()),
+        top_tags,
+        uptime_seconds: 0,
+    }
+}
+
+fn snapshot(blog: &Blog) -> Result<Stats, Error> {
+    if !blog.stats.enabled {
+        return Err(Error::NotFound);
+    }
+
+    let mut cache = blog
+        .stats_cache
+        .0
+        .lock()
+        .map_err(|_| anyhow::anyhow!("stats cache poisoned"))?;
+    let stats = match &*cache {
+        Some((computed, stats)) if computed.elapsed() < REFRESH_INTERVAL => stats.clone(),
+        _ => {
+            let stats = compute(blog);
+            *cache = Some((Instant::now(), stats.clone()));
+            stats
+        }
+    };
+
+    // Uptime doesn't touch any stored state, so it stays live instead of being cached.
+    Ok(Stats {
+        uptime_seconds: (Utc::now() - blog.started).num_seconds(),
+        ..stats
+    })
+}
+
+pub async fn http_get_stats(data: Data<Blog>) -> Result<Html<String>, Error> {
+    let stats = snapshot(&data)?;
+    Ok(Html(html::stats(&stats)))
+}
+
+pub async fn http_get_stats_json(data: Data<Blog>) -> Result<Response, Error> {
+    let stats = snapshot(&data)?;
+    Ok(([(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")], Json(stats)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration as ChronoDuration;
+
+    use super::*;
+    use crate::{testing, Post};
+
+    fn tagged(i: i64, tags: &[&str]) -> Post {
+        Post::builder(
+            testing::AUTHOR,
+            testing::epoch() + ChronoDuration::days(i),
+            format!("Post {}", i),
+        )
+        .tags(tags.iter().map(|t| t.to_string()).collect())
+        .build()
+        .unwrap()
+    }
+
+    fn blog(posts: Vec<Post>) -> Blog {
+        Blog::new(
+            testing::HOSTNAME,
+            vec![testing::author(testing::AUTHOR)],
+            posts,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn top_tags_are_ordered_and_truncated() {
+        // Tag `t<n>` is used on n posts, so counts run from 1 to 12. `Rust` and `rust` merge into a
+        // count of 3, tying with `t03`.
+        let mut posts = vec![];
+        for n in 1..=12 {
+            for _ in 0..n {
+                posts.push(tagged(posts.len() as i64, &[&format!("t{:02}", n)]));
+            }
+        }
+        posts.push(tagged(posts.len() as i64, &["Rust"]));
+        posts.push(tagged(posts.len() as i64, &["rust"]));
+        posts.push(tagged(posts.len() as i64, &["rust"]));
+
+        let stats = compute(&blog(posts));
+        let top = stats
+            .top_tags
+            .iter()
+            .map(|t| (t.tag.as_str(), t.posts))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            top,
+            [
+                ("t12", 12),
+                ("t11", 11),
+                ("t10", 10),
+                ("t09", 9),
+                ("t08", 8),
+                ("t07", 7),
+                ("t06", 6),
+                ("t05", 5),
+                ("t04", 4),
+                ("rust", 3),
+            ]
+        );
+        assert!(!top.contains(&("t03", 3)), "ties are broken by name");
+    }
+
+    #[test]
+    fn hides_followers_when_asked() {
+        let author = crate::Author::builder(testing::AUTHOR, "Alice")
+            .followers(vec![
+                url::Url::parse("https://example.com/users/bob").unwrap()
+            ])
+            .build()
+            .unwrap();
+        let mut blog = Blog::new(testing::HOSTNAME, vec![author], vec![]).unwrap();
+        assert_eq!(compute(&blog).followers, Some(1));
+        blog.stats.hide_followers = true;
+        assert_eq!(compute(&blog).followers, None);
+    }
+
+    #[test]
+    fn snapshot_is_reused_within_the_hour_with_live_uptime() {
+        let mut blog = testing::blog();
+        blog.started = Utc::now() - ChronoDuration::hours(5);
+        let first = snapshot(&blog).unwrap();
+        assert_eq!(first.total_posts, 4);
+
+        // Whatever is cached is served until the hour is up, apart from the uptime.
+        let stale = Stats {
+            total_posts: 999,
+            ..first.clone()
+        };
+        *blog.stats_cache.0.lock().unwrap() = Some((Instant::now(), stale));
+        blog.started = Utc::now() - ChronoDuration::hours(7);
+        let second = snapshot(&blog).unwrap();
+        assert_eq!(second.total_posts, 999);
+        assert!(second.uptime_seconds >= 7 * 3600);
+        assert!(first.uptime_seconds < 6 * 3600);
+    }
+
+    #[test]
+    fn disabled_stats_are_not_found() {
+        let mut blog = testing::blog();
+        blog.stats.enabled = false;
+        assert!(matches!(snapshot(&blog), Err(Error::NotFound)));
+    }
+}
@@ -0,0 +1,70 @@
+//! Fixtures for building a [`Blog`] in tests and tools without hand-writing every field.
+//!
+//! The blog keeps all of its state in memory, so a fixture blog behaves exactly like a running
+//! one; [`config`] wraps it in a debug-mode federation config ready for [`build_router`].
+//!
+//! [`build_router`]: crate::build_router
+
+use activitypub_federation::config::FederationConfig;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use url::Url;
+
+use crate::{Author, Blog, Error, Post};
+
+pub const HOSTNAME: &str = "http://localhost:3000";
+pub const DOMAIN: &str = "localhost:3000";
+pub const AUTHOR: &str = "alice";
+
+pub fn epoch() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+}
+
+pub fn author(name: &str) -> Author {
+    Author::builder(name, name.to_uppercase())
+        .bio("A fixture author.")
+        .build()
+        .expect("fixture author is valid")
+}
+
+pub fn post(author: &str, published: DateTime<Utc>, title: &str) -> Post {
+    Post::builder(author, published, title)
+        .content(format!("<p>{}</p>", title))
+        .build()
+        .expect("fixture post is valid")
+}
+
+// One author with a post of every kind, a day apart.
+pub fn posts(author: &str) -> Vec<Post> {
+    let day = Duration::days(1);
+    vec![
+        post(author, epoch(), "A note"),
+        Post::builder(author, epoch() + day, "An article")
+            .article()
+            .content("<p>Long form.</p>")
+            .tags(vec!["rust".into()])
+            .build()
+            .expect("fixture post is valid"),
+        Post::builder(author, epoch() + day * 2, "A share")
+            .share(Url::parse("https://example.com/").expect("fixture url is valid"))
+            .content("<p>Worth reading.</p>")
+            .build()
+            .expect("fixture post is valid"),
+        Post::builder(author, epoch() + day * 3, "A question")
+            .question(vec!["Yes".into(), "No".into()], Some(epoch() + day * 10))
+            .build()
+            .expect("fixture post is valid"),
+    ]
+}
+
+pub fn blog() -> Blog {
+    Blog::new(HOSTNAME, vec![author(AUTHOR)], posts(AUTHOR)).expect("fixture blog is valid")
+}
+
+pub async fn config(blog: Blog) -> Result<FederationConfig<Blog>, Error> {
+    Ok(FederationConfig::builder()
+        .domain(DOMAIN)
+        .app_data(blog)
+        .debug(true)
+        .build()
+        .await?)
+}
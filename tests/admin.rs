@@ -0,0 +1,95 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use blog::testing;
+use common::{router, send, Reply};
+
+const TOKEN: &str = "secret";
+
+// The admin routes of every schema version. /admin/meta lists the routes from the same table the
+// admin router is built from, so any change to them shows up here. Changing the routes means
+// adding a new version here and bumping `SCHEMA_VERSION`; never edit an existing entry.
+fn routes(schema_version: u64) -> &'static [&'static str] {
+    match schema_version {
+        1 => &[
+            "GET /admin/meta",
+            "GET /admin/metrics",
+            "GET /admin/load-errors",
+            "GET /admin/authors/:name/policy",
+        ],
+        version => panic!("no route list recorded for schema version {}", version),
+    }
+}
+
+async fn admin_get(router: &axum::Router, path: &str, token: Option<&str>) -> Reply {
+    let mut request = Request::get(path);
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    send(
+        router,
+        request.body(Body::empty()).expect("request is valid"),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn route_list_matches_schema_version() {
+    let mut blog = testing::blog();
+    blog.admin_token = Some(TOKEN.into());
+    let router = router(blog).await;
+
+    let reply = admin_get(&router, "/admin/meta", Some(TOKEN)).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    let meta = reply.json();
+    let version = meta["schemaVersion"]
+        .as_u64()
+        .expect("schemaVersion is a number");
+    let served = meta["routes"]
+        .as_array()
+        .expect("routes is an array")
+        .iter()
+        .map(|route| route.as_str().expect("route is a string"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        served,
+        routes(version),
+        "admin routes changed without bumping SCHEMA_VERSION"
+    );
+
+    // Every listed route is really mounted: unauthenticated requests are refused rather than
+    // falling through to the 404 page.
+    for route in &served {
+        let path = route
+            .strip_prefix("GET ")
+            .expect("admin routes are GET")
+            .replace(":name", testing::AUTHOR);
+        let reply = admin_get(&router, &path, None).await;
+        assert_eq!(reply.status, StatusCode::UNAUTHORIZED, "{}", route);
+        let reply = admin_get(&router, &path, Some(TOKEN)).await;
+        assert_eq!(reply.status, StatusCode::OK, "{}", route);
+    }
+}
+
+#[tokio::test]
+async fn meta_reports_features_and_limits() {
+    let mut blog = testing::blog();
+    blog.admin_token = Some(TOKEN.into());
+    blog.limits.admin = blog::RouteLimit::new(3, std::time::Duration::from_secs(2), 1024);
+    let router = router(blog).await;
+
+    let meta = admin_get(&router, "/admin/meta", Some(TOKEN)).await.json();
+    assert_eq!(
+        meta["features"],
+        serde_json::json!(["testing", "panic-route"])
+    );
+    let admin = &meta["limits"]["classes"]["admin"];
+    assert_eq!(admin["maxConcurrent"], 3);
+    assert_eq!(admin["timeoutMs"], 2000);
+    assert_eq!(admin["maxBody"], 1024);
+    assert!(meta["limits"]["classes"]["federation"].is_object());
+    assert!(meta["limits"]["classes"]["pages"].is_object());
+}
@@ -0,0 +1,93 @@
+//! The blog mounted under `/blog`, with webfinger still answered at the domain root, as when a
+//! bigger site proxies `/.well-known` and `/blog/` to it.
+
+mod common;
+
+use axum::http::StatusCode;
+use blog::testing;
+use common::{fetch, get, router};
+use serde_json::Value;
+
+fn url(value: &Value) -> &str {
+    value
+        .as_str()
+        .unwrap_or_else(|| panic!("{} is not a url", value))
+}
+
+fn assert_prefixed(value: &Value) {
+    let prefix = format!("{}/blog/", testing::HOSTNAME);
+    assert!(
+        url(value).starts_with(&prefix),
+        "{} is outside /blog",
+        value
+    );
+}
+
+#[tokio::test]
+async fn federation_urls_stay_under_the_base_path() {
+    let router = router(testing::blog().with_base_path("/blog")).await;
+
+    let reply = get(
+        &router,
+        &format!(
+            "/.well-known/webfinger?resource=acct:alice@{}",
+            testing::DOMAIN
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(reply.status, StatusCode::OK);
+    let webfinger = reply.json();
+    let actor = webfinger["links"]
+        .as_array()
+        .expect("links is an array")
+        .iter()
+        .find(|link| link["rel"] == "self")
+        .expect("webfinger links the actor")["href"]
+        .clone();
+    assert_prefixed(&actor);
+
+    let person = fetch(&router, url(&actor)).await;
+    for field in ["id", "url", "inbox", "outbox", "followers", "following"] {
+        assert_prefixed(&person[field]);
+    }
+    assert_prefixed(&person["publicKey"]["id"]);
+
+    let outbox = fetch(&router, url(&person["outbox"])).await;
+    assert_prefixed(&outbox["first"]);
+    let page = fetch(&router, url(&outbox["first"])).await;
+    let items = page["orderedItems"].as_array().expect("items are an array");
+    assert!(!items.is_empty());
+    for create in items {
+        assert_prefixed(&create["id"]);
+        assert_prefixed(&create["actor"]);
+        let object = &create["object"];
+        assert_prefixed(&object["id"]);
+        assert_prefixed(&object["url"]);
+
+        // The HTML page of every post is served where its `url` says.
+        let path = url(&object["url"])
+            .strip_prefix(testing::HOSTNAME)
+            .expect("post url is on the blog");
+        assert_eq!(get(&router, path, None).await.status, StatusCode::OK);
+
+        for tag in object["tag"].as_array().into_iter().flatten() {
+            assert_prefixed(&tag["href"]);
+            let collection = fetch(&router, url(&tag["href"])).await;
+            assert_prefixed(&collection["first"]);
+        }
+    }
+}
+
+#[tokio::test]
+async fn nothing_is_served_outside_the_base_path() {
+    let router = router(testing::blog().with_base_path("/blog")).await;
+    for path in ["/users/alice", "/users/alice/outbox", "/tags/rust"] {
+        assert_eq!(
+            get(&router, path, Some(common::ACTIVITY_JSON)).await.status,
+            StatusCode::NOT_FOUND,
+            "{}",
+            path
+        );
+    }
+}
@@ -0,0 +1,68 @@
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, Request, StatusCode},
+    Router,
+};
+use blog::{build_router, testing, Blog};
+use serde_json::Value;
+use tower::ServiceExt;
+
+pub const ACTIVITY_JSON: &str = "application/activity+json";
+
+pub struct Reply {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl Reply {
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap_or_else(|err| panic!("{}: {:?}", err, self.body))
+    }
+}
+
+pub async fn router(blog: Blog) -> Router {
+    build_router(testing::config(blog).await.expect("fixture config builds"))
+}
+
+pub async fn send(router: &Router, request: Request<Body>) -> Reply {
+    let response = router
+        .clone()
+        .oneshot(request)
+        .await
+        .expect("router is infallible");
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("body can be read");
+    Reply {
+        status,
+        headers,
+        body: String::from_utf8(body.to_vec()).expect("body is utf-8"),
+    }
+}
+
+pub async fn get(router: &Router, uri: &str, accept: Option<&str>) -> Reply {
+    let mut request = Request::get(uri);
+    if let Some(accept) = accept {
+        request = request.header(header::ACCEPT, accept);
+    }
+    send(
+        router,
+        request.body(Body::empty()).expect("request is valid"),
+    )
+    .await
+}
+
+// Fetches a document by its id, the way a remote server dereferences a link we handed out.
+pub async fn fetch(router: &Router, id: &str) -> Value {
+    let path = id
+        .strip_prefix(testing::HOSTNAME)
+        .unwrap_or_else(|| panic!("{} is not on {}", id, testing::HOSTNAME));
+    let reply = get(router, path, Some(ACTIVITY_JSON)).await;
+    assert_eq!(reply.status, StatusCode::OK, "GET {}: {}", path, reply.body);
+    reply.json()
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use axum::http::{header, StatusCode};
+use blog::testing;
+use common::{get, router};
+use url::form_urlencoded;
+
+fn oembed(url: &str) -> String {
+    format!(
+        "oembed?url={}",
+        form_urlencoded::byte_serialize(url.as_bytes()).collect::<String>()
+    )
+}
+
+#[tokio::test]
+async fn oembed_describes_posts_on_this_blog() {
+    let router = router(testing::blog()).await;
+    for url in [
+        format!("{}/blog/a-note", testing::HOSTNAME),
+        format!("{}/embed/a-note", testing::HOSTNAME),
+    ] {
+        let reply = get(&router, &format!("/{}", oembed(&url)), None).await;
+        assert_eq!(reply.status, StatusCode::OK, "{}", url);
+        let oembed = reply.json();
+        assert_eq!(oembed["type"], "rich");
+        assert_eq!(oembed["title"], "A note");
+        assert!(oembed["html"]
+            .as_str()
+            .unwrap()
+            .contains(&format!("src=\"{}/embed/a-note\"", testing::HOSTNAME)));
+    }
+}
+
+#[tokio::test]
+async fn oembed_rejects_urls_off_the_blog() {
+    let router = router(testing::blog().with_base_path("/blog")).await;
+    assert_eq!(
+        get(
+            &router,
+            &format!("/blog/{}", oembed("http://localhost:3000/blog/blog/a-note")),
+            None
+        )
+        .await
+        .status,
+        StatusCode::OK
+    );
+
+    for url in [
+        // Another origin: host, scheme and port each count.
+        "http://example.com/blog/blog/a-note",
+        "https://localhost:3000/blog/blog/a-note",
+        "http://localhost:4000/blog/blog/a-note",
+        // Outside the base path, or not a post route.
+        "http://localhost:3000/blog/a-note",
+        "http://localhost:3000/other/blog/a-note",
+        "http://localhost:3000/blog/users/alice",
+        "http://localhost:3000/blog/blog/a-note/extra",
+        "not a url",
+    ] {
+        let reply = get(&router, &format!("/blog/{}", oembed(url)), None).await;
+        assert_eq!(reply.status, StatusCode::NOT_FOUND, "{}", url);
+    }
+}
+
+#[tokio::test]
+async fn oembed_only_speaks_json() {
+    let router = router(testing::blog()).await;
+    let url = format!("{}/blog/a-note", testing::HOSTNAME);
+    let json = format!("/{}&format=json", oembed(&url));
+    assert_eq!(get(&router, &json, None).await.status, StatusCode::OK);
+    let xml = format!("/{}&format=xml", oembed(&url));
+    assert_eq!(
+        get(&router, &xml, None).await.status,
+        StatusCode::NOT_IMPLEMENTED
+    );
+}
+
+#[tokio::test]
+async fn only_embeds_can_be_framed() {
+    let router = router(testing::blog()).await;
+    for path in ["/blog/a-note", "/users/alice", "/tags/rust", "/stats"] {
+        let reply = get(&router, path, None).await;
+        assert_eq!(reply.status, StatusCode::OK, "{}", path);
+        assert_eq!(reply.headers[header::X_FRAME_OPTIONS], "DENY", "{}", path);
+    }
+
+    let reply = get(&router, "/embed/a-note", None).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert!(reply.headers.get(header::X_FRAME_OPTIONS).is_none());
+    assert_eq!(
+        reply.headers[header::CONTENT_SECURITY_POLICY],
+        "frame-ancestors *"
+    );
+    assert!(reply.body.contains("A note"));
+}
@@ -0,0 +1,108 @@
+//! Opting out of search: the robots meta on HTML pages and `indexable` on what we federate.
+
+mod common;
+
+use axum::http::StatusCode;
+use blog::{testing, Author, Blog, Post};
+use chrono::Duration;
+use common::{fetch, get, router};
+use serde_json::{json, Value};
+
+const NOINDEX: &str = "<meta name=\"robots\" content=\"noindex\">";
+
+// alice follows the global policy and bob opts out; each has a post overriding their choice.
+fn blog() -> Blog {
+    let post = |author: &str, days: i64, title: &str, indexable: Option<bool>| {
+        let builder = Post::builder(author, testing::epoch() + Duration::days(days), title);
+        match indexable {
+            Some(indexable) => builder.indexable(indexable),
+            None => builder,
+        }
+        .build()
+        .expect("fixture post is valid")
+    };
+    let bob = Author::builder("bob", "Bob")
+        .indexable(false)
+        .build()
+        .expect("fixture author is valid");
+    Blog::new(
+        testing::HOSTNAME,
+        vec![testing::author(testing::AUTHOR), bob],
+        vec![
+            post("alice", 0, "Alice default", None),
+            post("alice", 1, "Alice hidden", Some(false)),
+            post("bob", 2, "Bob default", None),
+            post("bob", 3, "Bob shown", Some(true)),
+        ],
+    )
+    .expect("fixture blog is valid")
+}
+
+#[tokio::test]
+async fn unindexable_pages_carry_noindex() {
+    let router = router(blog()).await;
+    for (path, indexable) in [
+        ("/blog/alice-default", true),
+        ("/blog/alice-hidden", false),
+        ("/blog/bob-default", false),
+        ("/blog/bob-shown", true),
+        ("/users/alice", true),
+        ("/users/bob", false),
+    ] {
+        let reply = get(&router, path, None).await;
+        assert_eq!(reply.status, StatusCode::OK, "{}", path);
+        assert_eq!(!reply.body.contains(NOINDEX), indexable, "{}", path);
+    }
+}
+
+fn defines_indexable(document: &Value) {
+    let context = document["@context"]
+        .as_array()
+        .expect("context is an array");
+    assert!(
+        context
+            .iter()
+            .any(|term| term["indexable"] == json!("toot:indexable")),
+        "{}",
+        document
+    );
+}
+
+#[tokio::test]
+async fn actors_and_objects_say_whether_they_are_indexable() {
+    let router = router(blog()).await;
+    for (name, indexable, objects) in [
+        (
+            "alice",
+            true,
+            [("Alice hidden", false), ("Alice default", true)],
+        ),
+        ("bob", false, [("Bob shown", true), ("Bob default", false)]),
+    ] {
+        let person = fetch(&router, &format!("{}/users/{}", testing::HOSTNAME, name)).await;
+        defines_indexable(&person);
+        assert_eq!(person["indexable"], indexable, "{}", name);
+
+        let outbox = fetch(&router, person["outbox"].as_str().unwrap()).await;
+        let page = fetch(&router, outbox["first"].as_str().unwrap()).await;
+        defines_indexable(&page);
+        let served = page["orderedItems"]
+            .as_array()
+            .expect("items are an array")
+            .iter()
+            .map(|create| {
+                let object = &create["object"];
+                assert_eq!(object["type"], "Note");
+                // Notes open with their title.
+                let content = object["content"].as_str().unwrap();
+                let title = content.lines().next().unwrap();
+                (title.to_string(), object["indexable"].clone())
+            })
+            .collect::<Vec<_>>();
+        let expected = objects
+            .iter()
+            .map(|(title, indexable)| (title.to_string(), json!(indexable)))
+            .collect::<Vec<_>>();
+        assert_eq!(served, expected, "{}", name);
+    }
+}
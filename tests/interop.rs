@@ -0,0 +1,219 @@
+//! What GoToSocial, Pleroma and Misskey require of the documents we serve.
+//!
+//! Every assertion here is a requirement some server enforces when following or fetching from
+//! the blog. Changing a serialized shape must keep this suite passing.
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use blog::{testing, Blog};
+use chrono::{DateTime, Duration};
+use common::{fetch, get, router, send, ACTIVITY_JSON};
+use serde_json::Value;
+
+// More posts than fit on one page, so collections have next and prev links to follow.
+fn long_blog() -> Blog {
+    let posts = (0..45)
+        .map(|i| {
+            testing::post(
+                testing::AUTHOR,
+                testing::epoch() + Duration::hours(i),
+                &format!("Post {}", i),
+            )
+        })
+        .collect();
+    Blog::new(
+        testing::HOSTNAME,
+        vec![testing::author(testing::AUTHOR)],
+        posts,
+    )
+    .expect("fixture blog is valid")
+}
+
+fn str_field<'a>(document: &'a Value, field: &str) -> &'a str {
+    document[field]
+        .as_str()
+        .unwrap_or_else(|| panic!("{} is not a string in {}", field, document))
+}
+
+#[tokio::test]
+async fn person_has_public_key_and_published() {
+    let router = router(testing::blog()).await;
+    let person = fetch(&router, &format!("{}/users/alice", testing::HOSTNAME)).await;
+
+    // GoToSocial rejects actors without either.
+    let id = str_field(&person, "id");
+    let key = &person["publicKey"];
+    assert_eq!(str_field(key, "owner"), id);
+    assert!(str_field(key, "id").starts_with(id));
+    assert!(str_field(key, "publicKeyPem").starts_with("-----BEGIN PUBLIC KEY-----"));
+    DateTime::parse_from_rfc3339(str_field(&person, "published")).expect("published is a date");
+
+    // Pleroma only reads publicKey when the security context defines it.
+    let context = person["@context"].as_array().expect("context is an array");
+    assert!(context.contains(&Value::from("https://w3id.org/security/v1")));
+
+    // Misskey needs an inbox and a username to create a remote user.
+    assert_eq!(str_field(&person, "preferredUsername"), "alice");
+    str_field(&person, "inbox");
+    str_field(&person, "outbox");
+}
+
+#[tokio::test]
+async fn webfinger_links_the_actor_document() {
+    let router = router(testing::blog()).await;
+    let reply = get(
+        &router,
+        &format!(
+            "/.well-known/webfinger?resource=acct:alice@{}",
+            testing::DOMAIN
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(reply.status, StatusCode::OK);
+
+    let webfinger = reply.json();
+    let links = webfinger["links"].as_array().expect("links is an array");
+    assert!(links.iter().any(|link| link["rel"] == "self"
+        && link["type"] == ACTIVITY_JSON
+        && link["href"] == format!("{}/users/alice", testing::HOSTNAME)));
+}
+
+#[tokio::test]
+async fn collections_link_their_first_page() {
+    let router = router(long_blog()).await;
+    for id in [
+        format!("{}/users/alice/outbox", testing::HOSTNAME),
+        format!("{}/tags/rust", testing::HOSTNAME),
+    ] {
+        let collection = fetch(&router, &id).await;
+        assert_eq!(collection["type"], "OrderedCollection");
+        assert_eq!(str_field(&collection, "id"), id);
+        assert!(collection["totalItems"].is_u64(), "{}", collection);
+        // GoToSocial won't page through a collection without `first`.
+        let first = fetch(&router, str_field(&collection, "first")).await;
+        assert_eq!(first["type"], "OrderedCollectionPage");
+        assert_eq!(str_field(&first, "partOf"), id);
+    }
+}
+
+#[tokio::test]
+async fn page_links_can_be_dereferenced() {
+    let router = router(long_blog()).await;
+    let outbox = fetch(
+        &router,
+        &format!("{}/users/alice/outbox", testing::HOSTNAME),
+    )
+    .await;
+    let total = outbox["totalItems"]
+        .as_u64()
+        .expect("totalItems is a number") as usize;
+
+    let mut forward = vec![];
+    let mut page = fetch(&router, str_field(&outbox, "first")).await;
+    assert!(page.get("prev").is_none(), "first page has no prev");
+    loop {
+        // Each page must be reachable again at its own id.
+        assert_eq!(fetch(&router, str_field(&page, "id")).await, page);
+        for item in page["orderedItems"].as_array().expect("items are an array") {
+            forward.push(str_field(item, "id").to_string());
+        }
+        match page.get("next") {
+            Some(next) => page = fetch(&router, next.as_str().expect("next is a url")).await,
+            None => break,
+        }
+    }
+    assert_eq!(forward.len(), total);
+
+    let mut backward = vec![];
+    loop {
+        let items = page["orderedItems"].as_array().expect("items are an array");
+        for item in items.iter().rev() {
+            backward.push(str_field(item, "id").to_string());
+        }
+        match page.get("prev") {
+            Some(prev) => page = fetch(&router, prev.as_str().expect("prev is a url")).await,
+            None => break,
+        }
+    }
+    backward.reverse();
+    assert_eq!(backward, forward);
+}
+
+#[tokio::test]
+async fn malformed_cursors_are_bad_requests() {
+    let router = router(testing::blog()).await;
+    let reply = get(
+        &router,
+        "/users/alice/outbox?page=true&after=garbage",
+        Some(ACTIVITY_JSON),
+    )
+    .await;
+    assert_eq!(reply.status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn hashtag_links_list_the_post() {
+    let post = blog::Post::builder(testing::AUTHOR, testing::epoch(), "Tagged")
+        .tags(vec!["Ärger".into(), "Rust".into()])
+        .build()
+        .expect("fixture post is valid");
+    let blog = Blog::new(
+        testing::HOSTNAME,
+        vec![testing::author(testing::AUTHOR)],
+        vec![post],
+    )
+    .expect("fixture blog is valid");
+    let router = router(blog).await;
+
+    let outbox = fetch(
+        &router,
+        &format!("{}/users/alice/outbox", testing::HOSTNAME),
+    )
+    .await;
+    let page = fetch(&router, str_field(&outbox, "first")).await;
+    let tags = page["orderedItems"][0]["object"]["tag"]
+        .as_array()
+        .expect("tags are an array");
+    assert_eq!(tags.len(), 2);
+    for tag in tags {
+        let collection = fetch(&router, str_field(tag, "href")).await;
+        assert_eq!(collection["totalItems"], 1, "{}", tag);
+    }
+}
+
+// Cloudflare and other shared caches key on the URL alone unless told otherwise.
+#[tokio::test]
+async fn negotiated_documents_vary_on_accept() {
+    let mut blog = testing::blog();
+    blog.domain_aliases = vec!["alias.example".into()];
+    let router = router(blog).await;
+
+    for uri in ["/users/alice", "/tags/rust"] {
+        for accept in [None, Some(ACTIVITY_JSON)] {
+            let reply = get(&router, uri, accept).await;
+            assert_eq!(reply.status, StatusCode::OK, "{} {:?}", uri, accept);
+            assert_eq!(
+                reply.headers[header::VARY],
+                "Accept",
+                "{} {:?}",
+                uri,
+                accept
+            );
+        }
+    }
+
+    for accept in [None, Some(ACTIVITY_JSON)] {
+        let mut request = Request::get("/users/alice").header(header::HOST, "alias.example");
+        if let Some(accept) = accept {
+            request = request.header(header::ACCEPT, accept);
+        }
+        let reply = send(&router, request.body(Body::empty()).unwrap()).await;
+        assert_eq!(reply.headers.get_all(header::VARY).iter().count(), 1);
+        assert_eq!(reply.headers[header::VARY], "Accept", "{:?}", accept);
+    }
+}
@@ -0,0 +1,50 @@
+//! Link preview metadata in the head of post pages.
+
+mod common;
+
+use blog::{testing, Blog, Post};
+use common::{get, router};
+
+#[tokio::test]
+async fn post_pages_describe_themselves_for_link_previews() {
+    let post = Post::builder(testing::AUTHOR, testing::epoch(), "Say \"hi\" & <leave>")
+        .slug("quoted")
+        .content("<p>She said \"no\", twice.</p>")
+        .tags(vec!["rust".into(), "web".into()])
+        .build()
+        .expect("fixture post is valid");
+    let blog = Blog::new(
+        testing::HOSTNAME,
+        vec![testing::author(testing::AUTHOR)],
+        vec![post],
+    )
+    .expect("fixture blog is valid");
+    let router = router(blog).await;
+
+    let body = get(&router, "/blog/quoted", None).await.body;
+    let head = &body[..body.find("</head>").expect("page has a head")];
+    for tag in [
+        "<meta property=\"og:type\" content=\"article\">".to_string(),
+        "<meta property=\"og:title\" content=\"Say &quot;hi&quot; &amp; &lt;leave&gt;\">".into(),
+        format!(
+            "<meta property=\"og:url\" content=\"{}/blog/quoted\">",
+            testing::HOSTNAME
+        ),
+        format!(
+            "<meta property=\"og:site_name\" content=\"{}\">",
+            testing::DOMAIN
+        ),
+        "<meta property=\"article:published_time\" content=\"2024-01-01T12:00:00Z\">".into(),
+        "<meta property=\"og:description\" content=\"She said &quot;no&quot;, twice.\">".into(),
+        "<meta property=\"article:tag\" content=\"rust\">".into(),
+        "<meta property=\"article:tag\" content=\"web\">".into(),
+        format!(
+            "<meta name=\"fediverse:creator\" content=\"@alice@{}\">",
+            testing::DOMAIN
+        ),
+    ] {
+        assert!(head.contains(&tag), "missing {} in {}", tag, head);
+    }
+    // The raw title never reaches an attribute.
+    assert!(!head.contains("content=\"Say \"hi\""));
+}
@@ -0,0 +1,66 @@
+//! Outbox latency with and without the serialized page cache, for a 1000-post blog.
+//!
+//! Run with `cargo test --release --test outbox_bench -- --ignored --nocapture`.
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use axum::{http::StatusCode, Router};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use blog::{testing, Blog};
+use common::{get, router, ACTIVITY_JSON};
+
+const POSTS: i64 = 1000;
+const REQUESTS: usize = 500;
+
+fn large_blog() -> Blog {
+    let posts = (0..POSTS)
+        .map(|i| {
+            testing::post(
+                testing::AUTHOR,
+                testing::epoch() + chrono::Duration::hours(i),
+                &format!("Post {}", i),
+            )
+        })
+        .collect();
+    Blog::new(
+        testing::HOSTNAME,
+        vec![testing::author(testing::AUTHOR)],
+        posts,
+    )
+    .expect("fixture blog is valid")
+}
+
+async fn p99(router: &Router, uri: &str) -> Duration {
+    let mut latencies = Vec::with_capacity(REQUESTS);
+    for _ in 0..REQUESTS {
+        let start = Instant::now();
+        let reply = get(router, uri, Some(ACTIVITY_JSON)).await;
+        latencies.push(start.elapsed());
+        assert_eq!(reply.status, StatusCode::OK);
+    }
+    latencies.sort();
+    latencies[REQUESTS * 99 / 100]
+}
+
+#[tokio::test]
+#[ignore]
+async fn outbox_first_page_p99() {
+    let router = router(large_blog()).await;
+
+    // A cursor past every post selects the same items as the first page, but cursor pages are
+    // never cached, so this is the cost of rendering the page on every request.
+    let everything = URL_SAFE_NO_PAD.encode("99999999999.0:~");
+    let rendered = p99(
+        &router,
+        &format!("/users/alice/outbox?page=true&after={}", everything),
+    )
+    .await;
+    let cached = p99(&router, "/users/alice/outbox?page=true").await;
+
+    println!(
+        "outbox first page p99 over {} posts: rendered {:?}, cached {:?}",
+        POSTS, rendered, cached
+    );
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use blog::testing;
+use common::{get, router, send};
+
+#[tokio::test]
+async fn panics_become_500s_with_a_request_id() {
+    let mut blog = testing::blog();
+    blog.admin_token = Some("secret".into());
+    let router = router(blog).await;
+
+    let panics = || async {
+        let request = Request::get("/admin/metrics")
+            .header(header::AUTHORIZATION, "Bearer secret")
+            .body(Body::empty())
+            .unwrap();
+        let metrics = send(&router, request).await.body;
+        metrics
+            .lines()
+            .find_map(|line| line.strip_prefix("panics_total "))
+            .expect("panics_total is reported")
+            .parse::<u64>()
+            .expect("panics_total is a number")
+    };
+
+    let before = panics().await;
+    let reply = get(&router, "/debug/panic", None).await;
+    assert_eq!(reply.status, StatusCode::INTERNAL_SERVER_ERROR);
+    let id = reply
+        .body
+        .strip_prefix("Internal Server Error (request id ")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .unwrap_or_else(|| panic!("no request id in {:?}", reply.body));
+    uuid::Uuid::parse_str(id).expect("request id is a uuid");
+    assert!(panics().await > before);
+
+    // The router keeps serving after a panic.
+    assert_eq!(
+        get(&router, "/users/alice", None).await.status,
+        StatusCode::OK
+    );
+}
@@ -0,0 +1,117 @@
+//! The HTML profile archive at /users/:name.
+
+mod common;
+
+use axum::http::{header, StatusCode};
+use blog::{testing, Blog, Post};
+use chrono::{Duration, TimeZone, Utc};
+use common::{get, router};
+
+// Thirty daily posts from 2023-12-20, so the first page spans New Year. The oldest is pinned and
+// every post but the tenths is tagged.
+fn blog() -> Blog {
+    let start = Utc.with_ymd_and_hms(2023, 12, 20, 12, 0, 0).unwrap();
+    let posts = (0..30)
+        .map(|i| {
+            let mut tags = vec![];
+            if i % 10 != 0 {
+                tags.push("rust".to_string());
+            }
+            Post::builder(
+                testing::AUTHOR,
+                start + Duration::days(i),
+                format!("Post {}", i),
+            )
+            .tags(tags)
+            .pinned(i == 0)
+            .build()
+            .expect("fixture post is valid")
+        })
+        .collect();
+    Blog::new(
+        testing::HOSTNAME,
+        vec![testing::author(testing::AUTHOR)],
+        posts,
+    )
+    .expect("fixture blog is valid")
+}
+
+fn position(body: &str, title: &str) -> Option<usize> {
+    body.find(&format!(">{}</a>", title))
+}
+
+fn shows(body: &str, title: &str) -> bool {
+    position(body, title).is_some()
+}
+
+#[tokio::test]
+async fn pinned_posts_come_first_on_the_first_page_only() {
+    let router = router(blog()).await;
+
+    let first = get(&router, "/users/alice", None).await;
+    assert_eq!(first.status, StatusCode::OK);
+    let pinned = position(&first.body, "Post 0").expect("pinned post is shown");
+    let newest = position(&first.body, "Post 29").expect("newest post is shown");
+    assert!(pinned < newest);
+    assert!(first.body.contains("<h2 id=\"pinned\">Pinned</h2>"));
+
+    let second = get(&router, "/users/alice?page=2", None).await;
+    assert_eq!(second.status, StatusCode::OK);
+    assert!(!shows(&second.body, "Post 0"));
+    assert!(!second.body.contains("Pinned"));
+    // The pinned post isn't counted against either page.
+    assert!(shows(&first.body, "Post 10") && !shows(&first.body, "Post 9"));
+    assert!(shows(&second.body, "Post 9") && shows(&second.body, "Post 1"));
+}
+
+#[tokio::test]
+async fn years_are_separated() {
+    let router = router(blog()).await;
+    let body = get(&router, "/users/alice", None).await.body;
+    let y2024 = body.find("<h2>2024</h2>").expect("2024 heading");
+    let y2023 = body.find("<h2>2023</h2>").expect("2023 heading");
+    assert!(y2024 < position(&body, "Post 12").unwrap());
+    assert!(position(&body, "Post 12").unwrap() < y2023);
+    assert!(y2023 < position(&body, "Post 11").unwrap());
+    assert_eq!(body.matches("<h2>2024</h2>").count(), 1);
+}
+
+#[tokio::test]
+async fn tag_filter_keeps_matching_posts() {
+    let router = router(blog()).await;
+    for uri in ["/users/alice?tag=rust", "/users/alice?tag=RUST"] {
+        let body = get(&router, uri, None).await.body;
+        assert!(shows(&body, "Post 29"), "{}", uri);
+        assert!(!shows(&body, "Post 20"), "{}", uri);
+        assert!(!shows(&body, "Post 0"), "{}", uri);
+        assert!(body.contains("Showing posts tagged #"), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn page_links_keep_the_tag() {
+    let router = router(blog()).await;
+    let first = get(&router, "/users/alice?tag=rust", None).await.body;
+    assert!(first.contains("href=\"?page=2&amp;tag=rust\" rel=\"next\""));
+
+    let second = get(&router, "/users/alice?page=2&tag=rust", None)
+        .await
+        .body;
+    assert!(second.contains("href=\"?page=1&amp;tag=rust\" rel=\"prev\""));
+    assert!(!second.contains("rel=\"next\""));
+
+    let untagged = get(&router, "/users/alice", None).await.body;
+    assert!(untagged.contains("href=\"?page=2\" rel=\"next\""));
+}
+
+#[tokio::test]
+async fn unknown_authors_get_the_not_found_page() {
+    let router = router(blog()).await;
+    let reply = get(&router, "/users/nobody", None).await;
+    assert_eq!(reply.status, StatusCode::NOT_FOUND);
+    assert!(reply.headers[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/html"));
+    assert!(reply.body.contains("<h1>Not Found</h1>"));
+}
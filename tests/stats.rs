@@ -0,0 +1,44 @@
+mod common;
+
+use axum::http::{header, StatusCode};
+use blog::testing;
+use common::{get, router};
+
+#[tokio::test]
+async fn disabled_stats_are_not_found() {
+    let mut blog = testing::blog();
+    blog.stats.enabled = false;
+    let router = router(blog).await;
+    for path in ["/stats", "/api/v1/stats"] {
+        assert_eq!(
+            get(&router, path, None).await.status,
+            StatusCode::NOT_FOUND,
+            "{}",
+            path
+        );
+    }
+}
+
+#[tokio::test]
+async fn json_stats_can_be_read_cross_origin() {
+    let router = router(testing::blog()).await;
+    let reply = get(&router, "/api/v1/stats", None).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    let stats = reply.json();
+    assert_eq!(stats["totalPosts"], 4);
+    assert_eq!(stats["followers"], 0);
+}
+
+#[tokio::test]
+async fn hidden_followers_are_left_out_of_both_formats() {
+    let mut blog = testing::blog();
+    blog.stats.hide_followers = true;
+    let router = router(blog).await;
+
+    let json = get(&router, "/api/v1/stats", None).await.json();
+    assert!(json.get("followers").is_none(), "{}", json);
+    let html = get(&router, "/stats", None).await.body;
+    assert!(!html.contains("Followers"));
+    assert!(html.contains("<dt>Posts</dt><dd>4</dd>"));
+}